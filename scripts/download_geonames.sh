@@ -0,0 +1,31 @@
+#!/bin/sh
+# Downloads the GeoNames dataset used for offline reverse geocoding into geonames/ (see geonames/README.md).
+# GeoNames only publishes its latest dump: the files are checked against the SHA-256 pinned in scripts/geonames.sha256,
+# and nothing is written if they changed. Run with --update to accept the current dump and pin its checksums.
+set -eu
+
+ROOT=$(cd "$(dirname "$0")/.." && pwd)
+URL=https://download.geonames.org/export/dump
+SUMS="$ROOT/scripts/geonames.sha256"
+TMP=$(mktemp -d)
+trap 'rm -rf "$TMP"' EXIT
+
+if [ "${1:-}" != "--update" ] && [ ! -f "$SUMS" ]; then
+  echo "No pinned checksums in $SUMS, run with --update to pin the current GeoNames dump." >&2
+  exit 1
+fi
+
+for file in admin1CodesASCII.txt countryInfo.txt cities15000.zip; do
+  curl -fsSL -o "$TMP/$file" "$URL/$file"
+done
+# Cities are only available zipped, the archive is not kept so that it is not bundled
+unzip -q "$TMP/cities15000.zip" cities15000.txt -d "$TMP"
+rm "$TMP/cities15000.zip"
+
+if [ "${1:-}" = "--update" ]; then
+  (cd "$TMP" && sha256sum admin1CodesASCII.txt countryInfo.txt cities15000.txt) > "$SUMS"
+  echo "Pinned the checksums of the current GeoNames dump in $SUMS, commit it."
+else
+  (cd "$TMP" && sha256sum --quiet -c "$SUMS")
+fi
+mv "$TMP"/*.txt "$ROOT/geonames/"
@@ -2,10 +2,13 @@ use serde::{Deserialize, Serialize};
 use yewdux::store::Store;
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Store)]
+#[serde(default)]
 pub struct Settings {
     pub theme: Theme,
     pub language: Option<String>,
     pub force_win_header: bool,
+    pub thumbnails_batch_cpu_limit: u8, // Percentage of the time a batch thumbnails generation can use a CPU core
+    pub thumbnails_cache_limit: u32,    // Size limit in MB of the thumbnails stored in the app cache directory, 0 for unlimited
 }
 
 impl Default for Settings {
@@ -14,6 +17,8 @@ impl Default for Settings {
             theme: Theme::System,
             language: None,
             force_win_header: false,
+            thumbnails_batch_cpu_limit: 50,
+            thumbnails_cache_limit: 2048,
         }
     }
 }
@@ -1,12 +1,16 @@
 use serde::{Deserialize, Serialize};
 use yewdux::store::Store;
 
+use crate::metadata::{HomeZone, MetadataStrip};
+use crate::ratings::RatingFilter;
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
 #[serde(default)]
 pub struct GalleryData {
     pub current_left_tab: u16,
     pub files_tab_selected_dir: Vec<String>,
     pub zoom_grid: f64,
+    pub rating_filter: RatingFilter, // Filter of the fast filtering tab
 }
 
 impl Default for GalleryData {
@@ -15,12 +19,83 @@ impl Default for GalleryData {
             current_left_tab: 0,
             files_tab_selected_dir: vec![],
             zoom_grid: 1.0,
+            rating_filter: RatingFilter::default(),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Store)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Store)]
 #[serde(default)]
 pub struct GallerySettings {
     pub test: String,
+    pub thumbnails_format: ThumbnailsFormat,
+    pub thumbnails_quality: u8, // 0 to 100, ignored for PNG
+    pub thumbnails_filter: ThumbnailsFilter,
+    pub thumbnails_sharpening: bool, // Unsharp mask applied after resizing
+    pub thumbnails_location: ThumbnailsLocation,
+    pub sync_ratings_to_xmp: bool,   // Writes ratings and color labels to Xmp.xmp.Rating and Xmp.xmp.Label
+    pub sync_tags_to_xmp: bool,      // Imports and writes tags as keywords, in Xmp.dc.subject, Xmp.lr.hierarchicalSubject and IPTC
+    pub export_strip: MetadataStrip, // Last used export options
+    pub home_zones: Vec<HomeZone>,
+}
+
+impl Default for GallerySettings {
+    fn default() -> Self {
+        Self {
+            test: String::new(),
+            thumbnails_format: ThumbnailsFormat::default(),
+            thumbnails_quality: 80,
+            thumbnails_filter: ThumbnailsFilter::default(),
+            thumbnails_sharpening: false,
+            thumbnails_location: ThumbnailsLocation::default(),
+            sync_ratings_to_xmp: false,
+            sync_tags_to_xmp: false,
+            export_strip: MetadataStrip::default(),
+            home_zones: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailsFormat {
+    #[default]
+    Jpeg,
+    WebP,
+    Png, // Always used for pictures with transparency
+}
+
+impl ThumbnailsFormat {
+    pub const ALL: [ThumbnailsFormat; 3] = [ThumbnailsFormat::Jpeg, ThumbnailsFormat::WebP, ThumbnailsFormat::Png];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ThumbnailsFormat::Jpeg => "jpg",
+            ThumbnailsFormat::WebP => "webp",
+            ThumbnailsFormat::Png => "png",
+        }
+    }
+    pub fn mimetype(&self) -> &'static str {
+        match self {
+            ThumbnailsFormat::Jpeg => "image/jpeg",
+            ThumbnailsFormat::WebP => "image/webp",
+            ThumbnailsFormat::Png => "image/png",
+        }
+    }
+}
+
+// Resampling filter used to downscale thumbnails, from the fastest to the sharpest.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailsFilter {
+    Box,
+    Bilinear,
+    CatmullRom,
+    #[default]
+    Lanczos3,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailsLocation {
+    #[default]
+    Gallery, // In the .thumbnails directory of the gallery
+    AppCache, // In the app cache directory, useful for synced or read-only galleries
 }
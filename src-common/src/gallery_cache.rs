@@ -8,15 +8,40 @@ use yew::Properties;
 pub struct PictureCache {
     pub path: String,
     pub uuid_generated: bool,
-    pub date: Option<String>,
+    pub date: Option<String>,              // Raw EXIF DateTimeOriginal
+    pub capture_date: Option<CaptureDate>, // Parsed date, with fallbacks when DateTimeOriginal is missing
     pub location: Option<(f64, f64, f64)>,
+    pub country: Option<String>, // Reverse geocoded from the location, like the region and the city
+    pub region: Option<String>,
+    pub city: Option<String>,
     pub orientation: Orientation,
     pub dimensions: (u32, u32),
-    pub camera: Option<String>,
+    pub camera: Option<String>, // Model
+    pub camera_make: Option<String>,
     pub focal_length: Option<f64>,
     pub exposure_time: Option<(u32, u32)>,
     pub iso_speed: Option<i32>,
     pub f_number: Option<f64>,
+    pub lens: Option<String>,
+    pub focal_length_35mm: Option<u32>,
+    pub exposure_bias: Option<f64>, // In EV
+    pub flash: Option<u16>,         // EXIF flash value, bit 0 is set if the flash fired
+    pub metering_mode: Option<u16>, // EXIF metering mode
+    pub white_balance: Option<u16>, // EXIF white balance: 0 for auto, 1 for manual
+    pub software: Option<String>,
+    pub offset_time_original: Option<String>, // Timezone of the date, formatted as ±HH:MM
+    pub offset_time: Option<String>,          // Timezone of the last modification date
+    pub gps_direction: Option<f64>,           // Direction the camera was pointing to, in degrees
+    pub file_size: u64,
+    pub file_format: Option<String>, // Upper case extension of the file
+    pub color: Option<(u8, u8, u8)>, // Dominant color, painted while the thumbnail loads
+    pub title: Option<String>,
+    pub keywords: Vec<String>,
+    pub hierarchical_keywords: Vec<String>, // Xmp.lr.hierarchicalSubject, levels separated by |
+    pub rating: Option<i32>,                // From 1 to 5 stars, -1 if rejected
+    pub label: Option<String>,              // XMP color label
+    pub description: Option<String>,
+    pub copyright: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default, Clone, Properties)]
@@ -45,3 +70,95 @@ pub enum Orientation {
     Rotate90VerticalFlip,
     Rotate270,
 }
+
+impl Orientation {
+    // True if the picture must be rotated by 90° or 270°, swapping its width and height.
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(
+            self,
+            Orientation::Rotate90 | Orientation::Rotate270 | Orientation::Rotate90HorizontalFlip | Orientation::Rotate90VerticalFlip
+        )
+    }
+}
+
+// Capture date of a picture, read from its metadata or guessed from its file.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CaptureDate {
+    pub local: i64,          // Local date and time as seconds since 1970-01-01 00:00:00, regardless of the timezone
+    pub offset: Option<i32>, // Offset of the timezone from UTC in seconds, if known
+    pub source: DateSource,
+}
+
+impl CaptureDate {
+    // Formats the local date as `YYYY-MM-DD HH:MM:SS`, followed by the timezone if known.
+    pub fn format(&self) -> String {
+        let (days, seconds) = (self.local.div_euclid(86400), self.local.rem_euclid(86400));
+        let (year, month, day) = civil_from_days(days);
+        let mut date = format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+        if let Some(offset) = self.offset {
+            let sign = if offset < 0 { '-' } else { '+' };
+            date.push_str(&format!(" {}{:02}:{:02}", sign, offset.abs() / 3600, offset.abs() / 60 % 60));
+        }
+        date
+    }
+}
+
+// Converts a number of days since 1970-01-01 to a (year, month, day) date of the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// Where the capture date was found, from the most to the least reliable.
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DateSource {
+    #[default]
+    DateTimeOriginal, // Exif.Photo.DateTimeOriginal
+    CreateDate,     // Exif.Photo.DateTimeDigitized
+    XmpDateCreated, // Xmp.photoshop.DateCreated
+    FileName,       // Like IMG_20230415_103020.jpg or Screenshot 2023-04-15 at 10.30.20.png
+    FileModified,   // Last modification time of the file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_from_days_converts_days_since_epoch() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19462), (2023, 4, 15));
+        assert_eq!(civil_from_days(-135081), (1600, 2, 29));
+    }
+
+    #[test]
+    fn capture_date_format_includes_timezone() {
+        let mut date = CaptureDate {
+            local: 1681554620,
+            offset: None,
+            source: DateSource::DateTimeOriginal,
+        };
+        assert_eq!(date.format(), "2023-04-15 10:30:20");
+        date.offset = Some(-19800);
+        assert_eq!(date.format(), "2023-04-15 10:30:20 -05:30");
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use yewdux::store::Store;
 
@@ -7,6 +9,51 @@ pub struct HierarchyConfig {
     pub nested_levels: Vec<Vec<HierarchyGroupRule>>,
 }
 
+impl HierarchyConfig {
+    // The hierarchy view displays the pictures matching at least one of the rules of the first level (all the pictures if none).
+    pub fn matches_tags(&self, picture_tags: &[String]) -> bool {
+        match self.nested_levels.first() {
+            Some(rules) if !rules.is_empty() => rules.iter().any(|rule| rule.matches_tags(picture_tags)),
+            _ => true,
+        }
+    }
+    // Updates the tags filters after tags were merged (Some(target id)) or deleted (None). Returns true if a filter changed.
+    // A rule whose included tags were all deleted is removed, as it would match every picture instead of none.
+    pub fn replace_tags(&mut self, replaced: &HashMap<String, Option<String>>) -> bool {
+        let mut changed = false;
+        for rules in &mut self.nested_levels {
+            rules.retain_mut(|rule| {
+                let mut keep = true;
+                for filter in &mut rule.filters {
+                    if let HierarchyFilterType::Tags(include, exclude) = &mut filter.filter_type {
+                        let had_included = !include.is_empty();
+                        changed |= replace_tag_ids(include, replaced) | replace_tag_ids(exclude, replaced);
+                        keep &= !had_included || !include.is_empty();
+                    }
+                }
+                changed |= !keep;
+                keep
+            });
+        }
+        changed
+    }
+}
+fn replace_tag_ids(tags: &mut Vec<String>, replaced: &HashMap<String, Option<String>>) -> bool {
+    if !tags.iter().any(|tag| replaced.contains_key(tag)) {
+        return false;
+    }
+    let mut new_tags: Vec<String> = Vec::new();
+    for tag in tags.drain(..) {
+        if let Some(tag) = replaced.get(&tag).cloned().unwrap_or(Some(tag)) {
+            if !new_tags.contains(&tag) {
+                new_tags.push(tag);
+            }
+        }
+    }
+    *tags = new_tags;
+    true
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Store)]
 #[serde(default)]
 pub struct HierarchyGroupRule {
@@ -14,6 +61,13 @@ pub struct HierarchyGroupRule {
     pub groups: Vec<HierarchyGroup>,
 }
 
+impl HierarchyGroupRule {
+    // A rule applies to the pictures matching all of its filters.
+    pub fn matches_tags(&self, picture_tags: &[String]) -> bool {
+        self.filters.iter().all(|filter| filter.filter_type.matches_tags(picture_tags))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Store)]
 pub enum HierarchyFilterType {
     #[default]
@@ -23,9 +77,24 @@ pub enum HierarchyFilterType {
     DateRange(String, String),      // From, To
 }
 
+impl HierarchyFilterType {
+    // Tags filters keep the pictures having at least one of the included tags (if any), and none of the excluded ones.
+    // The other filters do not depend on tags.
+    pub fn matches_tags(&self, picture_tags: &[String]) -> bool {
+        match self {
+            HierarchyFilterType::Tags(include, exclude) => {
+                (include.is_empty() || include.iter().any(|tag| picture_tags.contains(tag))) && !exclude.iter().any(|tag| picture_tags.contains(tag))
+            }
+            _ => true,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Store)]
 #[serde(default)]
-pub struct HierarchyFilter {}
+pub struct HierarchyFilter {
+    pub filter_type: HierarchyFilterType,
+}
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Store)]
 pub enum HierarchyGroupType {
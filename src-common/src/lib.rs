@@ -3,4 +3,8 @@ pub mod data_structs;
 pub mod gallery;
 pub mod gallery_cache;
 pub mod menu;
+pub mod metadata;
+pub mod ratings;
 pub mod hierarchy_config;
+pub mod tags;
+pub mod thumbnails;
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+
+// Metadata changes applied to one or many pictures. Fields left to None are not modified, empty strings remove the tag.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct MetadataEdit {
+    pub date: Option<String>,                      // EXIF format: YYYY:MM:DD HH:MM:SS
+    pub offset_time: Option<String>,               // Timezone of the date, formatted as ±HH:MM
+    pub date_shift: Option<i64>,                   // Seconds added to the other dates (digitized, modified, XMP) along with a date shift
+    pub location: Option<(f64, f64, Option<f64>)>, // Latitude, longitude and altitude if known
+    pub remove_location: bool,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub copyright: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub hierarchical_keywords: Option<Vec<String>>, // Levels separated by |, like People|Alice
+    pub rating: Option<i32>,                        // From -1 (rejected) to 5 stars, 0 removes the rating
+    pub label: Option<String>,                      // XMP color label
+}
+
+impl MetadataEdit {
+    pub fn is_empty(&self) -> bool {
+        *self == MetadataEdit::default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum MetadataEditError {
+    InvalidDate(String),
+    InvalidLatitude(f64),
+    InvalidLongitude(f64),
+    InvalidTimezone(String),
+    InvalidRating(i32),
+    ReferenceWithoutDate,     // The reference picture of a date shift has no date
+    TrackReadFailed(String),  // Path of the GPS track that could not be read
+    NoTrackPoints,            // The GPS tracks have no timed point
+    WriteFailed(Vec<String>), // Paths of the pictures whose metadata could not be written
+}
+
+impl MetadataEditError {
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            MetadataEditError::InvalidDate(_) => "metadata-error-invalid-date",
+            MetadataEditError::InvalidLatitude(_) => "metadata-error-invalid-latitude",
+            MetadataEditError::InvalidLongitude(_) => "metadata-error-invalid-longitude",
+            MetadataEditError::InvalidTimezone(_) => "metadata-error-invalid-timezone",
+            MetadataEditError::InvalidRating(_) => "metadata-error-invalid-rating",
+            MetadataEditError::ReferenceWithoutDate => "metadata-error-reference-without-date",
+            MetadataEditError::TrackReadFailed(_) => "metadata-error-track-read-failed",
+            MetadataEditError::NoTrackPoints => "metadata-error-no-track-points",
+            MetadataEditError::WriteFailed(_) => "metadata-error-write-failed",
+        }
+    }
+}
+
+// Shift of the capture date of pictures, to fix the wrong clock of a camera.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DateShift {
+    pub offset: DateShiftOffset,
+    pub timezone: Option<String>, // Written to OffsetTimeOriginal, formatted as ±HH:MM
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum DateShiftOffset {
+    Seconds(i64),
+    // The reference picture was taken at this date (EXIF format).
+    // Only the pictures of the same camera are shifted, by the offset between the reference date and its current date.
+    Reference { id: String, date: String },
+}
+
+// Capture date of a picture before and after a date shift. Pictures without date are not shifted.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DateShiftPreview {
+    pub id: String,
+    pub old_date: String,
+    pub new_date: String,
+}
+
+// Location of pictures taken without GPS, found by matching their capture time with GPS tracks recorded at the same time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Geotag {
+    pub tracks: Vec<String>,      // Paths of GPX, KML or GeoJSON files
+    pub clock_offset: i64,        // Seconds added to the capture time of the pictures, to fix the camera clock
+    pub timezone: Option<String>, // Timezone of the pictures without recorded timezone, formatted as ±HH:MM. UTC by default
+    pub max_gap: i64,             // Maximum time in seconds between a picture and the track points it is located from
+    pub overwrite: bool,          // Pictures already located are skipped otherwise
+}
+
+// Location found for a picture, None if the tracks have no point close enough in time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GeotagPreview {
+    pub id: String,
+    pub location: Option<(f64, f64, Option<f64>)>, // Latitude, longitude and altitude if known
+}
+
+// Metadata removed from the exported copies of the pictures.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct MetadataStrip {
+    pub all: bool,
+    pub gps: bool,
+    pub serials: bool, // Serial numbers of the camera body and lens, including in maker notes
+    pub uid: bool,     // Xmp.PicturesManagerClementGre.uid
+}
+
+// Area around a private place, like a home. The location of pictures taken inside is never exported.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct HomeZone {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius: f64, // In meters
+}
+
+impl HomeZone {
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        // Haversine distance, in meters
+        let (d_lat, d_lon) = ((latitude - self.latitude).to_radians(), (longitude - self.longitude).to_radians());
+        let a = (d_lat / 2.0).sin().powi(2) + self.latitude.to_radians().cos() * latitude.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+        6371000.0 * 2.0 * a.sqrt().asin() <= self.radius
+    }
+}
+
+// Copies of pictures to a directory, with their metadata partially or totally removed. Originals are never modified.
+// The location is also removed from the pictures taken in one of the home zones of the gallery settings.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Export {
+    pub destination: String,
+    pub strip: MetadataStrip,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ExportError {
+    InvalidDestination(String), // The destination does not exist or is not a directory
+    ExportFailed(Vec<String>),  // Paths of the pictures that could not be exported
+}
+
+impl ExportError {
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            ExportError::InvalidDestination(_) => "export-error-invalid-destination",
+            ExportError::ExportFailed(_) => "export-error-failed",
+        }
+    }
+}
+
+// All the tags of a metadata namespace of a picture (Exif.Photo, Iptc.Application2, Xmp.dc...), listed by the metadata inspector.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MetadataGroup {
+    pub name: String,
+    pub tags: Vec<MetadataTag>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MetadataTag {
+    pub key: String,         // Full key, like Exif.Photo.FNumber
+    pub label: String,       // Human readable name given by exiv2, or the tag name if unknown
+    pub value: String,       // Raw value
+    pub interpreted: String, // Value interpreted by exiv2, like F2.8 for 28/10
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+// Rating, flag and color label of a picture, stored in the gallery.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct PictureRating {
+    pub stars: u8, // 0 to 5
+    pub flag: PictureFlag,
+    pub label: Option<ColorLabel>,
+}
+
+impl PictureRating {
+    pub fn is_empty(&self) -> bool {
+        *self == PictureRating::default()
+    }
+    pub fn apply(&mut self, change: RatingChange) {
+        match change {
+            RatingChange::Stars(stars) => self.stars = stars.min(5),
+            RatingChange::Flag(flag) => self.flag = flag,
+            RatingChange::Label(label) => self.label = label,
+        }
+    }
+    // Applies an XMP rating, keeping what it can't represent: the stars of rejected pictures and the pick flag.
+    pub fn apply_xmp_rating(&mut self, xmp_rating: i32) {
+        if xmp_rating < 0 {
+            self.flag = PictureFlag::Reject;
+        } else {
+            self.stars = xmp_rating.min(5) as u8;
+            if self.flag == PictureFlag::Reject {
+                self.flag = PictureFlag::None;
+            }
+        }
+    }
+    // XMP rating: stars, or -1 for rejected pictures.
+    pub fn get_xmp_rating(&self) -> i32 {
+        if self.flag == PictureFlag::Reject {
+            -1
+        } else {
+            self.stars as i32
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PictureFlag {
+    #[default]
+    None,
+    Pick,
+    Reject,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorLabel {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl ColorLabel {
+    pub const ALL: [ColorLabel; 5] = [
+        ColorLabel::Red,
+        ColorLabel::Yellow,
+        ColorLabel::Green,
+        ColorLabel::Blue,
+        ColorLabel::Purple,
+    ];
+
+    // Value of Xmp.xmp.Label, as written by Lightroom, Bridge and darktable.
+    pub fn xmp_label(&self) -> &'static str {
+        match self {
+            ColorLabel::Red => "Red",
+            ColorLabel::Yellow => "Yellow",
+            ColorLabel::Green => "Green",
+            ColorLabel::Blue => "Blue",
+            ColorLabel::Purple => "Purple",
+        }
+    }
+    pub fn from_xmp_label(label: &str) -> Option<Self> {
+        ColorLabel::ALL
+            .into_iter()
+            .find(|color| color.xmp_label().eq_ignore_ascii_case(label.trim()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum RatingChange {
+    Stars(u8),
+    Flag(PictureFlag),
+    Label(Option<ColorLabel>),
+}
+
+// Filter of the fast filtering tab. Empty lists accept any value.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RatingFilter {
+    pub min_stars: u8,
+    pub flags: Vec<PictureFlag>,
+    pub labels: Vec<ColorLabel>,
+}
+
+impl RatingFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == RatingFilter::default()
+    }
+    pub fn matches(&self, rating: &PictureRating) -> bool {
+        rating.stars >= self.min_stars
+            && (self.flags.is_empty() || self.flags.contains(&rating.flag))
+            && (self.labels.is_empty() || rating.label.map_or(false, |label| self.labels.contains(&label)))
+    }
+}
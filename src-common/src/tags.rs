@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+// Group of tags, stored in the gallery by id. In an exclusive group (not multiple), a picture has at most one tag.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TagGroup {
+    pub name: String,
+    pub multiple: bool,
+    pub tags: HashMap<String, Tag>, // Tags by id
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Tag {
+    pub name: String,
+    pub color: String,         // Formatted as #RRGGBB
+    pub pictures: Vec<String>, // Tagged pictures uid
+}
+
+impl TagGroup {
+    // Pictures having more than one tag of the group, which is not allowed if the group is exclusive.
+    pub fn get_multiple_tagged_pictures(&self) -> Vec<String> {
+        let mut counts: HashMap<&String, usize> = HashMap::new();
+        for picture in self.tags.values().flat_map(|tag| &tag.pictures) {
+            *counts.entry(picture).or_default() += 1;
+        }
+        let mut pictures: Vec<String> = counts.into_iter().filter(|(_, count)| *count > 1).map(|(id, _)| id.clone()).collect();
+        pictures.sort();
+        pictures
+    }
+    pub fn has_tag_named(&self, name: &str, except_id: Option<&str>) -> bool {
+        self.tags
+            .iter()
+            .any(|(id, tag)| Some(id.as_str()) != except_id && tag.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl Tag {
+    // Flat keyword of the tag, the last level of its name: a tag `Family|Alice` is written as `Alice` in Xmp.dc.subject.
+    pub fn get_keyword(&self) -> &str {
+        self.name.rsplit(KEYWORD_SEPARATOR).next().unwrap_or_default().trim()
+    }
+    pub fn add_pictures(&mut self, ids: &[String]) {
+        for id in ids {
+            if !self.pictures.contains(id) {
+                self.pictures.push(id.clone());
+            }
+        }
+    }
+}
+
+// Tags ids of every tagged picture, used to match the hierarchy tags filters.
+pub fn get_pictures_tags(tag_groups: &HashMap<String, TagGroup>) -> HashMap<String, Vec<String>> {
+    let mut pictures_tags: HashMap<String, Vec<String>> = HashMap::new();
+    for (tag_id, tag) in tag_groups.values().flat_map(|group| &group.tags) {
+        for picture in &tag.pictures {
+            pictures_tags.entry(picture.clone()).or_default().push(tag_id.clone());
+        }
+    }
+    pictures_tags
+}
+
+// Levels separator of the hierarchical keywords: a tag "Alice" in the group "People" is the keyword `People|Alice`.
+pub const KEYWORD_SEPARATOR: char = '|';
+// Color of the tags created from keywords.
+pub const DEFAULT_TAG_COLOR: &str = "#808080";
+// Group of the tags created from keywords without hierarchy.
+pub const DEFAULT_KEYWORDS_GROUP: &str = "Keywords";
+
+// The tags of the default keywords group come from flat keywords, they are written back without hierarchy.
+pub fn get_hierarchical_keyword(group: &TagGroup, tag: &Tag) -> Option<String> {
+    if group.name.eq_ignore_ascii_case(DEFAULT_KEYWORDS_GROUP) {
+        return None;
+    }
+    Some(format!("{}{}{}", group.name, KEYWORD_SEPARATOR, tag.name))
+}
+
+pub fn is_valid_tag_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum TagError {
+    GroupNotFound(String),
+    TagNotFound(String),
+    EmptyName,
+    SeparatorInGroupName,
+    NameAlreadyUsed(String),
+    InvalidColor(String),
+    ExclusiveConflict(Vec<String>), // Pictures that would have several tags of an exclusive group
+    WriteFailed(Vec<String>),       // Paths of the pictures whose keywords could not be written
+}
+
+impl TagError {
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            TagError::GroupNotFound(_) => "tag-error-group-not-found",
+            TagError::TagNotFound(_) => "tag-error-tag-not-found",
+            TagError::EmptyName => "tag-error-empty-name",
+            TagError::SeparatorInGroupName => "tag-error-separator-in-group-name",
+            TagError::NameAlreadyUsed(_) => "tag-error-name-already-used",
+            TagError::InvalidColor(_) => "tag-error-invalid-color",
+            TagError::ExclusiveConflict(_) => "tag-error-exclusive-conflict",
+            TagError::WriteFailed(_) => "tag-error-write-failed",
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use crate::gallery::ThumbnailsFormat;
+
+// Thumbnails with a higher priority are generated first.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ThumbnailPriority {
+    #[default]
+    Background,
+    Nearby, // Close to the visible area
+    Visible,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct ThumbnailsBatchProgress {
+    pub done: usize,
+    pub total: usize,
+    pub eta: Option<u64>, // Estimated remaining time in seconds
+    pub finished: bool,
+}
+
+// Tile pyramid of a picture, used to display very large pictures when zoomed in.
+// Level 0 is the full resolution (orientation applied), each level halves the previous one until the picture fits in a single tile.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct TilesInfo {
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    pub levels: u32,
+    pub format: ThumbnailsFormat,
+}
+
+impl TilesInfo {
+    pub fn get_level_dimensions(&self, level: u32) -> (u32, u32) {
+        let divisor = 1u64 << level;
+        let divide = |length: u32| (((length as u64 + divisor - 1) / divisor) as u32).max(1);
+        (divide(self.width), divide(self.height))
+    }
+    // Number of columns and rows of tiles of a level
+    pub fn get_level_tiles(&self, level: u32) -> (u32, u32) {
+        let (width, height) = self.get_level_dimensions(level);
+        (
+            (width + self.tile_size - 1) / self.tile_size,
+            (height + self.tile_size - 1) / self.tile_size,
+        )
+    }
+}
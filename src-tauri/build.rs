@@ -1,3 +1,28 @@
+use std::path::Path;
+
+// GeoNames dataset used for offline reverse geocoding, bundled as a resource (see geonames/README.md).
+const GEONAMES_DIR: &str = "../geonames";
+const CITIES_FILES: [&str; 4] = ["cities500.txt", "cities1000.txt", "cities5000.txt", "cities15000.txt"];
+
 fn main() {
+  check_geonames();
   tauri_build::build()
 }
+
+// The dataset is never downloaded by the build, which stays offline and reproducible: it is fetched once with scripts/download_geonames.sh.
+// Without it, the application builds but pictures are not geocoded.
+fn check_geonames() {
+  println!("cargo:rerun-if-changed={}", GEONAMES_DIR);
+  let dir = Path::new(GEONAMES_DIR);
+  let missing: Vec<&str> = ["admin1CodesASCII.txt", "countryInfo.txt"]
+    .into_iter()
+    .filter(|file| !dir.join(file).is_file())
+    .chain((!CITIES_FILES.iter().any(|file| dir.join(file).is_file())).then_some("cities15000.txt"))
+    .collect();
+  if !missing.is_empty() {
+    println!(
+      "cargo:warning=GeoNames dataset incomplete (missing {}), pictures will not be geocoded. Run scripts/download_geonames.sh to fetch it.",
+      missing.join(", ")
+    );
+  }
+}
@@ -8,12 +8,15 @@ use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use tauri::{Window, Wry};
 
-use pm_common::gallery_cache::Orientation;
+use pm_common::gallery::{GallerySettings, ThumbnailsFilter};
+use pm_common::gallery_cache::{CaptureDate, Orientation};
 
 use crate::utils::exif_utils::ExifFile;
 use crate::utils::files_utils::{path_from_unix_path_string, path_to_unix_path_string};
-use crate::utils::thumbnails::is_supported_img;
+use crate::utils::thumbnails::{get_thumbnail_fingerprint, is_supported_img};
 
+use super::gallery_ratings::get_xmp_picture_rating;
+use super::gallery_tags::import_picture_keywords;
 use super::windows_galleries::{WindowGallery, WindowsGalleriesState};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -21,21 +24,101 @@ use super::windows_galleries::{WindowGallery, WindowsGalleriesState};
 pub struct PictureCache {
     pub path: String, // In unix style, call get_path to get a valid path.
     pub uuid_generated: bool,
-    pub date: Option<String>,
+    pub date: Option<String>,              // Raw EXIF DateTimeOriginal
+    pub capture_date: Option<CaptureDate>, // Parsed date, with fallbacks when DateTimeOriginal is missing
     pub location: Option<(f64, f64, f64)>,
+    pub country: Option<String>, // Reverse geocoded from the location, like the region and the city
+    pub region: Option<String>,
+    pub city: Option<String>,
     pub orientation: Orientation,
     pub dimensions: (u32, u32),
-    pub camera: Option<String>,
+    pub camera: Option<String>, // Model
+    pub camera_make: Option<String>,
     pub focal_length: Option<f64>,
     pub exposure_time: Option<(u32, u32)>,
     pub iso_speed: Option<i32>,
     pub f_number: Option<f64>,
+    pub lens: Option<String>,
+    pub focal_length_35mm: Option<u32>,
+    pub exposure_bias: Option<f64>, // In EV
+    pub flash: Option<u16>,         // EXIF flash value, bit 0 is set if the flash fired
+    pub metering_mode: Option<u16>, // EXIF metering mode
+    pub white_balance: Option<u16>, // EXIF white balance: 0 for auto, 1 for manual
+    pub software: Option<String>,
+    pub offset_time_original: Option<String>, // Timezone of the date, formatted as ±HH:MM
+    pub offset_time: Option<String>,          // Timezone of the last modification date
+    pub gps_direction: Option<f64>,           // Direction the camera was pointing to, in degrees
+    pub file_size: u64,
+    pub file_format: Option<String>, // Upper case extension of the file
+    pub color: Option<(u8, u8, u8)>, // Dominant color, painted while the thumbnail loads
+    pub title: Option<String>,
+    pub keywords: Vec<String>,
+    pub hierarchical_keywords: Vec<String>, // Xmp.lr.hierarchicalSubject, levels separated by |
+    pub rating: Option<i32>,                // From 1 to 5 stars, -1 if rejected
+    pub label: Option<String>,              // XMP color label
+    pub description: Option<String>,
+    pub copyright: Option<String>,
 }
 
 impl PictureCache {
     pub fn get_path(&self) -> String {
         path_from_unix_path_string(self.path.clone())
     }
+    // Pictures are sorted by their local capture date, as seen by the photographer.
+    pub fn get_date_sort_key(&self) -> Option<i64> {
+        self.capture_date.map(|date| date.local)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ThumbnailCache {
+    pub source: ThumbnailSource,
+    pub fingerprint: ThumbnailFingerprint, // Fingerprint of the picture file when the thumbnail was generated
+    pub filter: Option<ThumbnailsFilter>,  // None if generated before the rendering settings were recorded
+    pub sharpening: bool,
+    pub transparent: bool, // Kept in PNG whatever the thumbnails format, as the picture uses transparency
+}
+
+impl ThumbnailCache {
+    pub fn new(source: ThumbnailSource, fingerprint: ThumbnailFingerprint, transparent: bool, settings: &GallerySettings) -> Self {
+        Self {
+            source,
+            fingerprint,
+            filter: Some(settings.thumbnails_filter),
+            sharpening: settings.thumbnails_sharpening,
+            transparent,
+        }
+    }
+    // Cache of thumbnails generated before it was recorded.
+    pub fn unknown(fingerprint: ThumbnailFingerprint) -> Self {
+        Self {
+            source: ThumbnailSource::Unknown,
+            fingerprint,
+            ..Default::default()
+        }
+    }
+    // Whether the thumbnail has been generated with the current rendering settings, None if unknown.
+    pub fn is_rendered_with(&self, settings: &GallerySettings) -> Option<bool> {
+        Some(self.filter? == settings.thumbnails_filter && self.sharpening == settings.thumbnails_sharpening)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum ThumbnailSource {
+    #[default]
+    Unknown, // Generated before the source was recorded
+    FullDecode,
+    EmbeddedPreview, // Generated from an EXIF thumbnail or preview image
+}
+
+// The thumbnail must be regenerated as soon as the fingerprint of its picture changes.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct ThumbnailFingerprint {
+    pub modified: u64, // Modification time in seconds since UNIX epoch
+    pub size: u64,
+    pub orientation: Orientation,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -46,6 +129,32 @@ pub struct PathsCache {
     pub pictures: Vec<String>,     // EXIF uid, sorted by date
 }
 
+impl PathsCache {
+    // Path is the list of the subdirectories names, relative to this directory.
+    pub fn get_dir(&self, path: &[String]) -> Option<&PathsCache> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        self.children.iter().find(|child| child.dir_name == path[0])?.get_dir(&path[1..])
+    }
+    // Sorts the pictures of this directory and of all its subdirectories, like when the cache is built.
+    pub fn sort_pictures_by_date(&mut self, datas_cache: &HashMap<String, PictureCache>) {
+        self.pictures
+            .sort_by_cached_key(|id| datas_cache.get(id).and_then(|picture| picture.get_date_sort_key()));
+        for child in &mut self.children {
+            child.sort_pictures_by_date(datas_cache);
+        }
+    }
+    // Pictures uid of this directory and of all its subdirectories.
+    pub fn get_pictures_recursive(&self) -> Vec<String> {
+        let mut pictures = self.pictures.clone();
+        for child in &self.children {
+            pictures.extend(child.get_pictures_recursive());
+        }
+        pictures
+    }
+}
+
 #[tauri::command]
 pub fn get_gallery_datas_cache(window: Window<Wry>, galleries_state: tauri::State<WindowsGalleriesState>) -> HashMap<String, PictureCache> {
     let galleries = galleries_state.get_galleries();
@@ -81,6 +190,36 @@ pub fn update_gallery_cache(
     let elapsed = start.elapsed();
     info!("Gallery cache updated {} pictures in {}ms", datas_cache.len(), elapsed.as_millis());
 
+    // Pictures without embedded preview got their color when their thumbnail was generated, it is kept while the thumbnail is up to date
+    for (id, picture) in datas_cache.iter_mut().filter(|(_, picture)| picture.color.is_none()) {
+        let previous_color = gallery.gallery.datas_cache.get(id).and_then(|previous| previous.color);
+        if let (Some(color), Some(thumbnail)) = (previous_color, gallery.gallery.thumbnails_cache.get(id)) {
+            if get_thumbnail_fingerprint(&gallery_path.join(picture.get_path()), picture.orientation).as_ref() == Some(&thumbnail.fingerprint) {
+                picture.color = Some(color);
+            }
+        }
+    }
+
+    // Pictures rated in other applications
+    for (id, picture) in &datas_cache {
+        if !gallery.gallery.ratings.contains_key(id) {
+            let rating = get_xmp_picture_rating(picture);
+            if !rating.is_empty() {
+                gallery.gallery.ratings.insert(id.clone(), rating);
+            }
+        }
+    }
+    gallery.gallery.ratings.retain(|id, _| datas_cache.contains_key(id));
+    for tag in gallery.gallery.tag_groups.values_mut().flat_map(|group| group.tags.values_mut()) {
+        tag.pictures.retain(|id| datas_cache.contains_key(id));
+    }
+    // Pictures tagged or untagged in other applications, compared with the previous scan
+    if gallery.gallery.settings.sync_tags_to_xmp {
+        for (id, picture) in &datas_cache {
+            import_picture_keywords(&mut gallery.gallery.tag_groups, id, picture, gallery.gallery.datas_cache.get(id));
+        }
+    }
+
     gallery.gallery.datas_cache = datas_cache.clone();
     gallery.gallery.paths_cache = paths_cache.clone();
     gallery.gallery.dates_cache = dates_cache.clone();
@@ -92,7 +231,7 @@ pub fn update_gallery_cache(
 pub fn read_dir_recursive(
     path: PathBuf,
     datas_cache: &mut HashMap<String, PictureCache>,
-    dates_cache: &mut Vec<(String, Option<String>)>,
+    dates_cache: &mut Vec<(String, Option<i64>)>,
     gallery_path: &Path,
 ) -> PathsCache {
     let mut paths_cache = PathsCache {
@@ -119,9 +258,10 @@ pub fn read_dir_recursive(
                     info!("Regenerating uid for file {:?} because this uid already exists.", path);
                     exif_file.regen_uid();
                 }
-                dates_cache.push((exif_file.uid.clone(), exif_file.get_date()));
-                datas_cache.insert(exif_file.uid.clone(), exif_file.to_picture_cache(path_to_unix_path_string(stripped_path)));
-                pictures.push((exif_file.uid.clone(), exif_file.get_date()));
+                let picture = exif_file.to_picture_cache(path_to_unix_path_string(stripped_path));
+                dates_cache.push((exif_file.uid.clone(), picture.get_date_sort_key()));
+                pictures.push((exif_file.uid.clone(), picture.get_date_sort_key()));
+                datas_cache.insert(exif_file.uid.clone(), picture);
             } else {
                 warn!("File {:?} does not support EXIF of XMP data.", path);
             }
@@ -1,33 +1,42 @@
 use std::{
     collections::HashMap,
-    fs::{create_dir_all, File},
+    fs::{canonicalize, create_dir_all, File},
     io::{BufReader, BufWriter},
     path::PathBuf,
+    thread,
 };
 
 use serde::{Deserialize, Serialize};
-use tauri::{State, Window, Wry};
+use tauri::{AppHandle, State, Window, Wry};
 
 use pm_common::gallery::{GalleryData, GallerySettings};
+use pm_common::hierarchy_config::HierarchyConfig;
+use pm_common::ratings::PictureRating;
+use pm_common::tags::TagGroup;
 
 use crate::gallery::windows_galleries::{WindowGallery, WindowsGalleriesState};
+use crate::utils::thumbnails::{get_thumbnails_dir, move_thumbnails};
 
 use super::{
-    gallery_cache::{PathsCache, PictureCache},
+    gallery_cache::{PathsCache, PictureCache, ThumbnailCache},
     gallery_clusters::{DatesClusters, LocationClusters},
-    gallery_tags::TagGroup,
 };
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct Gallery {
+    pub uid: String, // Identifies the gallery thumbnails in the app cache directory
     pub settings: GallerySettings,
-    pub data: GalleryData,                     // Mainly frontend UI data
-    pub tag_groups: HashMap<String, TagGroup>, // Tags groups by id
+    pub data: GalleryData,                       // Mainly frontend UI data
+    pub tag_groups: HashMap<String, TagGroup>,   // Tags groups by id
+    pub ratings: HashMap<String, PictureRating>, // Ratings, flags and color labels by picture uid
+    pub hierarchy: HierarchyConfig,              // Levels of the hierarchy view, with their filters
 
     pub datas_cache: HashMap<String, PictureCache>, // Pictures datas in function of their EXIF uid
     pub paths_cache: PathsCache,                    // Pictures EXIF uid, with directory structure (recursive structure)
     pub dates_cache: Vec<String>,                   // Pictures uid ordered by date
+    pub thumbnails_cache: HashMap<String, ThumbnailCache>, // Generated thumbnails info in function of their picture uid
+    pub thumbnails_batch: Option<Vec<String>>,      // Directory of the unfinished thumbnails batch generation, resumed at opening
 
     pub dates_clusters: Vec<DatesClusters>,
     pub location_clusters: Vec<LocationClusters>,
@@ -37,13 +46,24 @@ impl Gallery {
     pub fn load(path: &String) -> Gallery {
         let file = PathBuf::from(path).join("pictures_manager.json");
 
-        if file.exists() {
+        let mut gallery = if file.exists() {
             let file = File::open(&file).expect("Unable to open gallery file");
             let reader = BufReader::new(file);
             serde_json::from_reader(reader).expect("Unable to parse gallery file")
         } else {
             Gallery::default()
+        };
+        if gallery.uid.is_empty() {
+            gallery.uid = get_path_uid(path);
         }
+        gallery
+    }
+    // Sorts the dates and paths caches again after the dates of some pictures changed.
+    pub fn sort_pictures_by_date(&mut self) {
+        let datas_cache = &self.datas_cache;
+        self.dates_cache
+            .sort_by_cached_key(|id| datas_cache.get(id).and_then(|picture| picture.get_date_sort_key()));
+        self.paths_cache.sort_pictures_by_date(datas_cache);
     }
     pub fn save(&self, path: &String) {
         let dir = PathBuf::from(path);
@@ -57,6 +77,17 @@ impl Gallery {
     }
 }
 
+// The uid is derived from the gallery path so that galleries that can't be saved (read-only) get the same uid at every opening.
+// Once saved, the uid follows the gallery if it is moved.
+fn get_path_uid(path: &str) -> String {
+    let path = canonicalize(path).map_or(path.to_string(), |path| path.to_string_lossy().to_string());
+    // FNV-1a, which is stable across Rust versions unlike the std hasher
+    let hash = path
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    format!("{:X}", hash)
+}
+
 #[tauri::command]
 pub fn get_gallery_data(galleries: State<WindowsGalleriesState>, window: Window<Wry>) -> GalleryData {
     WindowGallery::get(&galleries.get_galleries(), &window).gallery.data.clone()
@@ -67,12 +98,37 @@ pub fn set_gallery_data(galleries: State<WindowsGalleriesState>, window: Window<
     WindowGallery::get_mut(&mut galleries, &window).gallery.data = data;
 }
 
+#[tauri::command]
+pub fn get_gallery_hierarchy(galleries: State<WindowsGalleriesState>, window: Window<Wry>) -> HierarchyConfig {
+    WindowGallery::get(&galleries.get_galleries(), &window).gallery.hierarchy.clone()
+}
+#[tauri::command]
+pub fn set_gallery_hierarchy(galleries: State<WindowsGalleriesState>, window: Window<Wry>, hierarchy: HierarchyConfig) {
+    let mut galleries = galleries.get_galleries();
+    let gallery = WindowGallery::get_mut(&mut galleries, &window);
+    gallery.gallery.hierarchy = hierarchy;
+    gallery.gallery.save(&gallery.path);
+}
 #[tauri::command]
 pub fn get_gallery_settings(galleries: State<WindowsGalleriesState>, window: Window<Wry>) -> GallerySettings {
     WindowGallery::get(&galleries.get_galleries(), &window).gallery.settings.clone()
 }
 #[tauri::command]
-pub fn set_gallery_settings(galleries: State<WindowsGalleriesState>, window: Window<Wry>, settings: GallerySettings) {
-    let mut galleries = galleries.get_galleries();
-    WindowGallery::get_mut(&mut galleries, &window).gallery.settings = settings;
+pub fn set_gallery_settings(app: AppHandle<Wry>, galleries: State<WindowsGalleriesState>, window: Window<Wry>, settings: GallerySettings) {
+    let moved = {
+        let mut galleries = galleries.get_galleries();
+        let gallery = WindowGallery::get_mut(&mut galleries, &window);
+        gallery.gallery.settings = settings;
+
+        let thumbnails_dir = get_thumbnails_dir(&app, &gallery.path, &gallery.gallery);
+        if thumbnails_dir != gallery.thumbnails_dir {
+            Some((std::mem::replace(&mut gallery.thumbnails_dir, thumbnails_dir.clone()), thumbnails_dir))
+        } else {
+            None
+        }
+    };
+    // Moving the thumbnails might take a while: missing thumbnails are generated again in the meantime.
+    if let Some((from, to)) = moved {
+        thread::spawn(move || move_thumbnails(&from, &to));
+    }
 }
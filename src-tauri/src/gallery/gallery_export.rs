@@ -0,0 +1,109 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use log::{info, warn};
+use tauri::api::dialog::blocking::FileDialogBuilder;
+use tauri::{State, Window, Wry};
+
+use pm_common::metadata::{Export, ExportError};
+
+use crate::utils::exif_utils::strip_metadata;
+
+use super::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+// Lets the user choose the export directory, returns its path.
+#[tauri::command]
+pub async fn pick_export_dir(window: Window<Wry>) -> Option<String> {
+    FileDialogBuilder::new()
+        .set_parent(&window)
+        .pick_folder()
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+// Copies the pictures to the destination directory, then removes the metadata of the copies.
+// Existing files are never replaced, copies are renamed instead. Returns the number of exported pictures.
+#[tauri::command]
+pub async fn export_pictures(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    ids: Vec<String>,
+    export: Export,
+) -> Result<usize, ExportError> {
+    let destination = PathBuf::from(&export.destination);
+    if !destination.is_dir() {
+        return Err(ExportError::InvalidDestination(export.destination));
+    }
+    let (pictures, home_zones): (Vec<_>, _) = {
+        let galleries = galleries_state.get_galleries();
+        let gallery = WindowGallery::get(&galleries, &window);
+        let pictures = ids
+            .iter()
+            .filter_map(|id| {
+                let picture = gallery.gallery.datas_cache.get(id)?;
+                Some((PathBuf::from(&gallery.path).join(picture.get_path()), picture.path.clone()))
+            })
+            .collect();
+        (pictures, gallery.gallery.settings.home_zones.clone())
+    };
+
+    let mut exported = 0;
+    let mut failed = Vec::new();
+    for (img_path, path) in pictures {
+        let Some(file_name) = img_path.file_name() else {
+            continue;
+        };
+        let target = get_free_path(&destination.join(file_name));
+        let result = fs::copy(&img_path, &target)
+            .and_then(|_| make_writable(&target))
+            .map_err(|e| e.to_string())
+            .and_then(|_| strip_metadata(&target, &export.strip, &home_zones).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => exported += 1,
+            Err(e) => {
+                warn!("Unable to export picture {:?} to {:?}: {}", img_path, target, e);
+                // A copy with its metadata must never be left behind
+                let _ = fs::remove_file(&target);
+                failed.push(path);
+            }
+        }
+    }
+    info!("📤 Exported {} pictures to {:?}, {} failed", exported, destination, failed.len());
+
+    if failed.is_empty() {
+        Ok(exported)
+    } else {
+        Err(ExportError::ExportFailed(failed))
+    }
+}
+
+// The permissions are copied with the file: a read-only original would give a copy that can't be stripped.
+fn make_writable(path: &Path) -> io::Result<()> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    if !permissions.readonly() {
+        return Ok(());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o200); // Writable by the owner only
+    }
+    #[cfg(not(unix))]
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions)
+}
+
+// Adds a number to the file name while the path is already used: `IMG_0001 (1).jpg`, `IMG_0001 (2).jpg`...
+fn get_free_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    let mut free_path = path.to_path_buf();
+    let mut index = 1;
+    while free_path.exists() {
+        free_path = path.with_file_name(format!("{} ({}){}", stem, index, extension));
+        index += 1;
+    }
+    free_path
+}
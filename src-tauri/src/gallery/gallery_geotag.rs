@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use tauri::api::dialog::blocking::FileDialogBuilder;
+use tauri::{State, Window, Wry};
+
+use pm_common::metadata::{Geotag, GeotagPreview, MetadataEdit, MetadataEditError};
+
+use crate::utils::exif_utils::parse_exif_timezone;
+use crate::utils::tracks::{locate_in_track, read_track_file, TrackPoint};
+
+use super::gallery_metadata::write_pictures_metadata;
+use super::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+// Lets the user choose the GPS track files, returns their paths.
+#[tauri::command]
+pub async fn pick_track_files(window: Window<Wry>) -> Vec<String> {
+    FileDialogBuilder::new()
+        .set_parent(&window)
+        .add_filter("GPX, KML, GeoJSON", &["gpx", "kml", "geojson", "json"])
+        .pick_files()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+// Lists the locations found for the pictures, without writing anything.
+#[tauri::command]
+pub async fn preview_geotag(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    ids: Vec<String>,
+    geotag: Geotag,
+) -> Result<Vec<GeotagPreview>, MetadataEditError> {
+    let points = read_tracks(&geotag)?;
+    let galleries = galleries_state.get_galleries();
+    get_geotag_locations(WindowGallery::get(&galleries, &window), &ids, &geotag, &points)
+}
+
+// Writes the GPS location of the pictures found in the tracks. Pictures not found are left untouched.
+#[tauri::command]
+pub async fn geotag_pictures(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    ids: Vec<String>,
+    geotag: Geotag,
+) -> Result<(), MetadataEditError> {
+    let points = read_tracks(&geotag)?;
+    let located = {
+        let galleries = galleries_state.get_galleries();
+        get_geotag_locations(WindowGallery::get(&galleries, &window), &ids, &geotag, &points)?
+    };
+    let edits = located
+        .into_iter()
+        .filter_map(|preview| {
+            let edit = MetadataEdit {
+                location: Some(preview.location?),
+                ..Default::default()
+            };
+            Some((preview.id, edit))
+        })
+        .collect();
+    write_pictures_metadata(&window, &galleries_state, edits)
+}
+
+// Points of all the tracks, sorted by time.
+fn read_tracks(geotag: &Geotag) -> Result<Vec<TrackPoint>, MetadataEditError> {
+    let mut points = Vec::new();
+    for path in &geotag.tracks {
+        points.extend(read_track_file(&PathBuf::from(path)).map_err(|_| MetadataEditError::TrackReadFailed(path.clone()))?);
+    }
+    if points.is_empty() {
+        return Err(MetadataEditError::NoTrackPoints);
+    }
+    points.sort_by_key(|point| point.time);
+    Ok(points)
+}
+
+// Pictures without date, and already located pictures unless overwritten, are not listed.
+fn get_geotag_locations(
+    gallery: &WindowGallery,
+    ids: &[String],
+    geotag: &Geotag,
+    points: &[TrackPoint],
+) -> Result<Vec<GeotagPreview>, MetadataEditError> {
+    let default_offset = match geotag.timezone.as_deref().filter(|timezone| !timezone.is_empty()) {
+        Some(timezone) => parse_exif_timezone(timezone).ok_or_else(|| MetadataEditError::InvalidTimezone(timezone.to_string()))?,
+        None => 0,
+    };
+    Ok(ids
+        .iter()
+        .filter_map(|id| {
+            let picture = gallery.gallery.datas_cache.get(id)?;
+            if picture.location.is_some() && !geotag.overwrite {
+                return None;
+            }
+            let date = picture.capture_date?;
+            let time = date.local - date.offset.unwrap_or(default_offset) as i64 + geotag.clock_offset;
+            Some(GeotagPreview {
+                id: id.clone(),
+                location: locate_in_track(points, time, geotag.max_gap),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,268 @@
+use std::path::PathBuf;
+
+use log::{info, warn};
+use tauri::{State, Window, Wry};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+
+use pm_common::gallery_cache::CaptureDate;
+use pm_common::metadata::{DateShift, DateShiftOffset, DateShiftPreview, MetadataEdit, MetadataEditError, MetadataGroup};
+use pm_common::ratings::{ColorLabel, PictureRating};
+
+use crate::utils::exif_utils::{format_exif_date, parse_exif_date, parse_exif_timezone, read_all_tags, ExifFile};
+use crate::utils::thumbnails::get_thumbnail_fingerprint;
+
+use super::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+// Edits the metadata of the pictures, then updates their cache and sends the new gallery cache to the frontend.
+// Nothing is written if the edit is invalid. Pictures that could not be written are listed in the error.
+#[tauri::command]
+pub async fn edit_pictures_metadata(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    ids: Vec<String>,
+    edit: MetadataEdit,
+) -> Result<(), MetadataEditError> {
+    validate_edit(&edit)?;
+    if edit.is_empty() {
+        return Ok(());
+    }
+    let edits = ids.into_iter().map(|id| (id, edit.clone())).collect();
+    write_pictures_metadata(&window, &galleries_state, edits)
+}
+
+// Lists all the EXIF, IPTC and XMP tags of a picture, for the metadata inspector.
+// None if the picture is not in the gallery or its metadata can't be read.
+#[tauri::command]
+pub async fn get_picture_metadata(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    id: String,
+) -> Result<Option<Vec<MetadataGroup>>, ()> {
+    let img_path = {
+        let galleries = galleries_state.get_galleries();
+        let gallery = WindowGallery::get(&galleries, &window);
+        let Some(picture) = gallery.gallery.datas_cache.get(&id) else {
+            return Ok(None);
+        };
+        PathBuf::from(&gallery.path).join(picture.get_path())
+    };
+    Ok(read_all_tags(&img_path))
+}
+
+// Lists the new capture dates of the pictures, without writing anything.
+#[tauri::command]
+pub fn preview_date_shift(
+    window: Window<Wry>,
+    galleries_state: State<WindowsGalleriesState>,
+    ids: Vec<String>,
+    shift: DateShift,
+) -> Result<Vec<DateShiftPreview>, MetadataEditError> {
+    let galleries = galleries_state.get_galleries();
+    get_shifted_dates(WindowGallery::get(&galleries, &window), &ids, &shift).map(|(_, shifted)| shifted)
+}
+
+// Shifts the capture dates of the pictures and sets their timezone. Pictures without date are left untouched.
+#[tauri::command]
+pub async fn shift_pictures_dates(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    ids: Vec<String>,
+    shift: DateShift,
+) -> Result<(), MetadataEditError> {
+    let (offset, shifted) = {
+        let galleries = galleries_state.get_galleries();
+        get_shifted_dates(WindowGallery::get(&galleries, &window), &ids, &shift)?
+    };
+    let edits = shifted
+        .into_iter()
+        .map(|preview| {
+            let edit = MetadataEdit {
+                date: Some(preview.new_date),
+                offset_time: shift.timezone.clone(),
+                date_shift: Some(offset.whole_seconds()),
+                ..Default::default()
+            };
+            (preview.id, edit)
+        })
+        .collect();
+    write_pictures_metadata(&window, &galleries_state, edits)
+}
+
+// Returns the offset of the shift and the new dates. Pictures dated by a fallback (file name, modification time)
+// are shifted too, their new date being written to DateTimeOriginal.
+fn get_shifted_dates(gallery: &WindowGallery, ids: &[String], shift: &DateShift) -> Result<(Duration, Vec<DateShiftPreview>), MetadataEditError> {
+    if let Some(timezone) = &shift.timezone {
+        if !timezone.is_empty() && parse_exif_timezone(timezone).is_none() {
+            return Err(MetadataEditError::InvalidTimezone(timezone.clone()));
+        }
+    }
+    let datas_cache = &gallery.gallery.datas_cache;
+
+    // With a reference picture, only the pictures of its camera are shifted.
+    let (offset, camera) = match &shift.offset {
+        DateShiftOffset::Seconds(seconds) => (Duration::seconds(*seconds), None),
+        DateShiftOffset::Reference { id, date } => {
+            let new_date = parse_exif_date(date).ok_or_else(|| MetadataEditError::InvalidDate(date.clone()))?;
+            let reference = datas_cache.get(id).ok_or(MetadataEditError::ReferenceWithoutDate)?;
+            let old_date = reference
+                .capture_date
+                .as_ref()
+                .and_then(get_local_date)
+                .ok_or(MetadataEditError::ReferenceWithoutDate)?;
+            (new_date - old_date, Some((&reference.camera_make, &reference.camera)))
+        }
+    };
+
+    let mut shifted = Vec::new();
+    for id in ids {
+        let Some(picture) = datas_cache.get(id) else {
+            continue;
+        };
+        if camera.map_or(false, |camera| camera != (&picture.camera_make, &picture.camera)) {
+            continue;
+        }
+        let Some(date) = picture.capture_date.as_ref().and_then(get_local_date) else {
+            continue;
+        };
+        let old_date = format_exif_date(date);
+        let new_date = date.checked_add(offset).ok_or_else(|| MetadataEditError::InvalidDate(old_date.clone()))?;
+        shifted.push(DateShiftPreview {
+            id: id.clone(),
+            old_date,
+            new_date: format_exif_date(new_date),
+        });
+    }
+    Ok((offset, shifted))
+}
+
+fn get_local_date(capture_date: &CaptureDate) -> Option<PrimitiveDateTime> {
+    let date = OffsetDateTime::from_unix_timestamp(capture_date.local).ok()?;
+    Some(PrimitiveDateTime::new(date.date(), date.time()))
+}
+
+// Writes the edits of the pictures, then updates their cache and sends the new gallery cache to the frontend.
+pub fn write_pictures_metadata(
+    window: &Window<Wry>,
+    galleries_state: &WindowsGalleriesState,
+    edits: Vec<(String, MetadataEdit)>,
+) -> Result<(), MetadataEditError> {
+    // Files are written without locking the gallery
+    let pictures: Vec<_> = {
+        let galleries = galleries_state.get_galleries();
+        let gallery = WindowGallery::get(&galleries, window);
+        edits
+            .into_iter()
+            .filter_map(|(id, edit)| {
+                let picture = gallery.gallery.datas_cache.get(&id)?;
+                let img_path = PathBuf::from(&gallery.path).join(picture.get_path());
+                // Thumbnails up to date before the edit are still up to date after, only the metadata changes.
+                let thumbnail_up_to_date = gallery.gallery.thumbnails_cache.get(&id).map_or(false, |cache| {
+                    get_thumbnail_fingerprint(&img_path, picture.orientation) == Some(cache.fingerprint)
+                });
+                Some((id, edit, img_path, picture.path.clone(), thumbnail_up_to_date))
+            })
+            .collect()
+    };
+    let date_changed = pictures.iter().any(|(_, edit, _, _, _)| edit.date.is_some());
+
+    let mut edited = Vec::new();
+    let mut failed = Vec::new();
+    for (id, edit, img_path, path, thumbnail_up_to_date) in pictures {
+        let result = ExifFile::new(img_path.clone())
+            .ok_or_else(|| "metadata not supported".to_string())
+            .and_then(|exif_file| exif_file.apply_edit(&edit).map_err(|e| e.to_string()))
+            .and_then(|_| ExifFile::new(img_path.clone()).ok_or_else(|| "unable to read metadata again".to_string()));
+        match result {
+            Ok(exif_file) => edited.push((id, edit, img_path, exif_file.to_picture_cache(path), thumbnail_up_to_date)),
+            Err(e) => {
+                warn!("Unable to edit metadata of picture {:?}: {}", img_path, e);
+                failed.push(path);
+            }
+        }
+    }
+    info!("📝 Edited metadata of {} pictures, {} failed", edited.len(), failed.len());
+
+    let (data, ratings) = {
+        let mut galleries = galleries_state.get_galleries();
+        let gallery = WindowGallery::get_mut(&mut galleries, window);
+        let mut ratings_changed = false;
+        for (id, edit, img_path, mut picture, thumbnail_up_to_date) in edited {
+            // The gallery ratings are kept in sync with the written XMP rating and label
+            if edit.rating.is_some() || edit.label.is_some() {
+                let rating = gallery.gallery.ratings.get(&id).copied().unwrap_or_default();
+                let rating = get_edited_rating(rating, &edit);
+                if rating.is_empty() {
+                    gallery.gallery.ratings.remove(&id);
+                } else {
+                    gallery.gallery.ratings.insert(id.clone(), rating);
+                }
+                ratings_changed = true;
+            }
+            if let Some(old_picture) = gallery.gallery.datas_cache.get(&id) {
+                picture.color = picture.color.or(old_picture.color);
+            }
+            if thumbnail_up_to_date {
+                if let (Some(cache), Some(fingerprint)) = (
+                    gallery.gallery.thumbnails_cache.get_mut(&id),
+                    get_thumbnail_fingerprint(&img_path, picture.orientation),
+                ) {
+                    cache.fingerprint = fingerprint;
+                }
+            }
+            gallery.gallery.datas_cache.insert(id, picture);
+        }
+        if date_changed {
+            gallery.gallery.sort_pictures_by_date();
+        }
+        gallery.gallery.save(&gallery.path);
+        let ratings = ratings_changed.then(|| gallery.gallery.ratings.clone());
+        ((gallery.gallery.datas_cache.clone(), gallery.gallery.paths_cache.clone()), ratings)
+    };
+    window.emit("gallery-cache-changed", data).unwrap();
+    if let Some(ratings) = ratings {
+        window.emit("gallery-ratings-changed", ratings).unwrap();
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(MetadataEditError::WriteFailed(failed))
+    }
+}
+
+fn get_edited_rating(mut rating: PictureRating, edit: &MetadataEdit) -> PictureRating {
+    if let Some(xmp_rating) = edit.rating {
+        rating.apply_xmp_rating(xmp_rating);
+    }
+    if let Some(label) = &edit.label {
+        rating.label = ColorLabel::from_xmp_label(label);
+    }
+    rating
+}
+
+fn validate_edit(edit: &MetadataEdit) -> Result<(), MetadataEditError> {
+    if let Some(date) = &edit.date {
+        if !date.is_empty() && parse_exif_date(date).is_none() {
+            return Err(MetadataEditError::InvalidDate(date.clone()));
+        }
+    }
+    if let Some(offset_time) = &edit.offset_time {
+        if !offset_time.is_empty() && parse_exif_timezone(offset_time).is_none() {
+            return Err(MetadataEditError::InvalidTimezone(offset_time.clone()));
+        }
+    }
+    if let Some(rating) = edit.rating {
+        if !(-1..=5).contains(&rating) {
+            return Err(MetadataEditError::InvalidRating(rating));
+        }
+    }
+    if let Some((latitude, longitude, _)) = edit.location {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(MetadataEditError::InvalidLatitude(latitude));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(MetadataEditError::InvalidLongitude(longitude));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use tauri::{State, Window, Wry};
+
+use pm_common::metadata::{MetadataEdit, MetadataEditError};
+use pm_common::ratings::{ColorLabel, PictureRating, RatingChange};
+
+use super::gallery_cache::PictureCache;
+use super::gallery_metadata::write_pictures_metadata;
+use super::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+#[tauri::command]
+pub fn get_gallery_ratings(window: Window<Wry>, galleries_state: State<WindowsGalleriesState>) -> HashMap<String, PictureRating> {
+    let galleries = galleries_state.get_galleries();
+    WindowGallery::get(&galleries, &window).gallery.ratings.clone()
+}
+
+// Changes the rating, flag or color label of the pictures and sends the new ratings to the frontend.
+// They are also written to the XMP metadata if enabled in the gallery settings.
+// The gallery ratings are the source of truth of the frontend, the metadata editor updates them too.
+#[tauri::command]
+pub async fn set_pictures_rating(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    ids: Vec<String>,
+    change: RatingChange,
+) -> Result<(), MetadataEditError> {
+    let (ratings, edits) = {
+        let mut galleries = galleries_state.get_galleries();
+        let gallery = WindowGallery::get_mut(&mut galleries, &window);
+        let sync = gallery.gallery.settings.sync_ratings_to_xmp;
+        let mut edits = Vec::new();
+        for id in ids {
+            if !gallery.gallery.datas_cache.contains_key(&id) {
+                continue;
+            }
+            let rating = gallery.gallery.ratings.entry(id.clone()).or_default();
+            rating.apply(change);
+            let rating = *rating;
+            if rating.is_empty() {
+                gallery.gallery.ratings.remove(&id);
+            }
+            if sync {
+                edits.push((id, get_rating_edit(&rating, change)));
+            }
+        }
+        gallery.gallery.save(&gallery.path);
+        (gallery.gallery.ratings.clone(), edits)
+    };
+    window.emit("gallery-ratings-changed", ratings).unwrap();
+
+    if edits.is_empty() {
+        return Ok(());
+    }
+    write_pictures_metadata(&window, &galleries_state, edits)
+}
+
+// Only the changed XMP tag is written: the flags are written with the rating, the pick flag has no XMP equivalent.
+fn get_rating_edit(rating: &PictureRating, change: RatingChange) -> MetadataEdit {
+    match change {
+        RatingChange::Stars(_) | RatingChange::Flag(_) => MetadataEdit {
+            rating: Some(rating.get_xmp_rating()),
+            ..Default::default()
+        },
+        RatingChange::Label(label) => MetadataEdit {
+            label: Some(label.map(|label| label.xmp_label().to_string()).unwrap_or_default()),
+            ..Default::default()
+        },
+    }
+}
+
+// Rating read from the XMP metadata, used for pictures not rated in the gallery yet.
+pub fn get_xmp_picture_rating(picture: &PictureCache) -> PictureRating {
+    let mut rating = PictureRating {
+        label: picture.label.as_deref().and_then(ColorLabel::from_xmp_label),
+        ..Default::default()
+    };
+    rating.apply_xmp_rating(picture.rating.unwrap_or(0));
+    rating
+}
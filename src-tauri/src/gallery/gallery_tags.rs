@@ -1,17 +1,534 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use serde::{Deserialize, Serialize};
+use tauri::{State, Window, Wry};
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct TagGroup {
-    pub name: String,
-    pub multiple: bool,
-    pub tags: HashMap<String, Tag>,
+use pm_common::metadata::{MetadataEdit, MetadataEditError};
+use pm_common::tags::{
+    get_hierarchical_keyword, is_valid_tag_color, Tag, TagError, TagGroup, DEFAULT_KEYWORDS_GROUP, DEFAULT_TAG_COLOR, KEYWORD_SEPARATOR,
+};
+
+use crate::utils::exif_utils::gen_new_uid;
+
+use super::gallery_cache::PictureCache;
+use super::gallery_data::Gallery;
+use super::gallery_metadata::write_pictures_metadata;
+use super::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+#[tauri::command]
+pub fn get_gallery_tag_groups(window: Window<Wry>, galleries_state: State<WindowsGalleriesState>) -> HashMap<String, TagGroup> {
+    let galleries = galleries_state.get_galleries();
+    WindowGallery::get(&galleries, &window).gallery.tag_groups.clone()
+}
+
+// Returns the id of the new group.
+#[tauri::command]
+pub async fn create_tag_group(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    name: String,
+    multiple: bool,
+) -> Result<String, TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        let name = check_group_name(tag_groups, &name, None)?;
+        let id = gen_new_uid();
+        tag_groups.insert(
+            id.clone(),
+            TagGroup {
+                name,
+                multiple,
+                tags: HashMap::new(),
+            },
+        );
+        Ok(id)
+    })
+}
+
+#[tauri::command]
+pub async fn rename_tag_group(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    name: String,
+) -> Result<(), TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        let name = check_group_name(tag_groups, &name, Some(&group_id))?;
+        get_group(tag_groups, &group_id)?.name = name;
+        Ok(())
+    })
+}
+
+// A group can become exclusive only if no picture has several of its tags.
+#[tauri::command]
+pub async fn set_tag_group_multiple(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    multiple: bool,
+) -> Result<(), TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        let group = get_group(tag_groups, &group_id)?;
+        if !multiple {
+            let conflicts = group.get_multiple_tagged_pictures();
+            if !conflicts.is_empty() {
+                return Err(TagError::ExclusiveConflict(conflicts));
+            }
+        }
+        group.multiple = multiple;
+        Ok(())
+    })
+}
+
+// Moves all the tags of the source group to the target group. Tags with the same name are merged.
+#[tauri::command]
+pub async fn merge_tag_groups(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    source_id: String,
+    target_id: String,
+) -> Result<(), TagError> {
+    update_tag_groups_merging(&window, &galleries_state, |tag_groups| {
+        let mut merged = HashMap::new();
+        if source_id == target_id {
+            return Ok(((), merged));
+        }
+        get_group(tag_groups, &target_id)?;
+        let source = get_group(tag_groups, &source_id)?.clone();
+        let mut target = tag_groups[&target_id].clone();
+        for (tag_id, tag) in source.tags {
+            let same_name = target
+                .tags
+                .iter()
+                .find(|(_, t)| t.name.eq_ignore_ascii_case(&tag.name))
+                .map(|(id, _)| id.clone());
+            match same_name {
+                Some(id) => {
+                    target.tags.get_mut(&id).unwrap().add_pictures(&tag.pictures);
+                    merged.insert(tag_id, id);
+                }
+                None => {
+                    target.tags.insert(tag_id, tag);
+                }
+            }
+        }
+        if !target.multiple {
+            let conflicts = target.get_multiple_tagged_pictures();
+            if !conflicts.is_empty() {
+                return Err(TagError::ExclusiveConflict(conflicts));
+            }
+        }
+        tag_groups.remove(&source_id);
+        tag_groups.insert(target_id, target);
+        Ok(((), merged))
+    })
+}
+
+#[tauri::command]
+pub async fn delete_tag_group(window: Window<Wry>, galleries_state: State<'_, WindowsGalleriesState>, group_id: String) -> Result<(), TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        tag_groups.remove(&group_id).ok_or(TagError::GroupNotFound(group_id)).map(|_| ())
+    })
+}
+
+// Returns the id of the new tag.
+#[tauri::command]
+pub async fn create_tag(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    name: String,
+    color: String,
+) -> Result<String, TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        let group = get_group(tag_groups, &group_id)?;
+        let name = check_tag_name(group, &name, None)?;
+        let color = check_color(&color)?;
+        let id = gen_new_uid();
+        group.tags.insert(
+            id.clone(),
+            Tag {
+                name,
+                color,
+                pictures: Vec::new(),
+            },
+        );
+        Ok(id)
+    })
+}
+
+#[tauri::command]
+pub async fn rename_tag(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    tag_id: String,
+    name: String,
+) -> Result<(), TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        let group = get_group(tag_groups, &group_id)?;
+        let name = check_tag_name(group, &name, Some(&tag_id))?;
+        get_tag(group, &tag_id)?.name = name;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn recolor_tag(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    tag_id: String,
+    color: String,
+) -> Result<(), TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        let color = check_color(&color)?;
+        get_tag(get_group(tag_groups, &group_id)?, &tag_id)?.color = color;
+        Ok(())
+    })
+}
+
+// Tags the pictures of the source tags with the target tag, then deletes the source tags. All the tags must be in the same group.
+#[tauri::command]
+pub async fn merge_tags(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    source_ids: Vec<String>,
+    target_id: String,
+) -> Result<(), TagError> {
+    update_tag_groups_merging(&window, &galleries_state, |tag_groups| {
+        let group = get_group(tag_groups, &group_id)?;
+        get_tag(group, &target_id)?;
+        for id in &source_ids {
+            get_tag(group, id)?;
+        }
+        let mut merged = HashMap::new();
+        for id in source_ids.iter().filter(|id| **id != target_id) {
+            if let Some(source) = group.tags.remove(id) {
+                group.tags.get_mut(&target_id).unwrap().add_pictures(&source.pictures);
+                merged.insert(id.clone(), target_id.clone());
+            }
+        }
+        Ok(((), merged))
+    })
+}
+
+#[tauri::command]
+pub async fn delete_tag(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    tag_id: String,
+) -> Result<(), TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        get_group(tag_groups, &group_id)?
+            .tags
+            .remove(&tag_id)
+            .ok_or(TagError::TagNotFound(tag_id))
+            .map(|_| ())
+    })
+}
+
+// In an exclusive group, the pictures lose their other tag of the group. Unknown pictures are ignored.
+#[tauri::command]
+pub async fn tag_pictures(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    tag_id: String,
+    ids: Vec<String>,
+) -> Result<(), TagError> {
+    let ids: Vec<String> = {
+        let galleries = galleries_state.get_galleries();
+        let datas_cache = &WindowGallery::get(&galleries, &window).gallery.datas_cache;
+        ids.into_iter().filter(|id| datas_cache.contains_key(id)).collect()
+    };
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        let group = get_group(tag_groups, &group_id)?;
+        get_tag(group, &tag_id)?;
+        if !group.multiple {
+            for (_, tag) in group.tags.iter_mut().filter(|(id, _)| **id != tag_id) {
+                tag.pictures.retain(|picture| !ids.contains(picture));
+            }
+        }
+        get_tag(group, &tag_id)?.add_pictures(&ids);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn untag_pictures(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    group_id: String,
+    tag_id: String,
+    ids: Vec<String>,
+) -> Result<(), TagError> {
+    update_tag_groups(&window, &galleries_state, |tag_groups| {
+        get_tag(get_group(tag_groups, &group_id)?, &tag_id)?
+            .pictures
+            .retain(|picture| !ids.contains(picture));
+        Ok(())
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct Tag {
-    pub name: String,
-    pub color: String,
-    pub pictures: Vec<String>,
-}
\ No newline at end of file
+// Applies a change to the tag groups of the window gallery. On success, the gallery is saved and the new tag groups are sent to the frontend.
+// Changes must check everything before modifying the tag groups, as nothing is reverted on error.
+// If the tags are synced to XMP, the keywords of the pictures whose tags changed are written.
+fn update_tag_groups<T>(
+    window: &Window<Wry>,
+    galleries_state: &WindowsGalleriesState,
+    update: impl FnOnce(&mut HashMap<String, TagGroup>) -> Result<T, TagError>,
+) -> Result<T, TagError> {
+    update_tag_groups_merging(window, galleries_state, |tag_groups| {
+        update(tag_groups).map(|result| (result, HashMap::new()))
+    })
+}
+// Like update_tag_groups, for changes that also return the ids of the merged tags with the id of the tag they were merged into.
+// The hierarchy tags filters then use the merged tags, and forget the deleted ones.
+fn update_tag_groups_merging<T>(
+    window: &Window<Wry>,
+    galleries_state: &WindowsGalleriesState,
+    update: impl FnOnce(&mut HashMap<String, TagGroup>) -> Result<(T, HashMap<String, String>), TagError>,
+) -> Result<T, TagError> {
+    let (result, tag_groups, hierarchy, edits) = {
+        let mut galleries = galleries_state.get_galleries();
+        let gallery = WindowGallery::get_mut(&mut galleries, window);
+        let previous_tags = get_pictures_tags_keywords(&gallery.gallery.tag_groups);
+        let previous_ids = get_tag_ids(&gallery.gallery.tag_groups);
+        let (result, merged) = update(&mut gallery.gallery.tag_groups)?;
+
+        let current_ids = get_tag_ids(&gallery.gallery.tag_groups);
+        let replaced: HashMap<String, Option<String>> = previous_ids
+            .difference(&current_ids)
+            .map(|id| (id.clone(), merged.get(id).cloned()))
+            .collect();
+        let hierarchy = gallery
+            .gallery
+            .hierarchy
+            .replace_tags(&replaced)
+            .then(|| gallery.gallery.hierarchy.clone());
+        gallery.gallery.save(&gallery.path);
+        let edits = if gallery.gallery.settings.sync_tags_to_xmp {
+            get_keywords_edits(&gallery.gallery, &previous_tags)
+        } else {
+            Vec::new()
+        };
+        (result, gallery.gallery.tag_groups.clone(), hierarchy, edits)
+    };
+    window.emit("gallery-tags-changed", tag_groups).unwrap();
+    if let Some(hierarchy) = hierarchy {
+        window.emit("gallery-hierarchy-changed", hierarchy).unwrap();
+    }
+
+    if !edits.is_empty() {
+        write_pictures_metadata(window, galleries_state, edits).map_err(|e| match e {
+            MetadataEditError::WriteFailed(paths) => TagError::WriteFailed(paths),
+            _ => TagError::WriteFailed(Vec::new()),
+        })?;
+    }
+    Ok(result)
+}
+
+fn get_tag_ids(tag_groups: &HashMap<String, TagGroup>) -> HashSet<String> {
+    tag_groups.values().flat_map(|group| group.tags.keys().cloned()).collect()
+}
+
+// Flat and hierarchical keywords of the tags of every tagged picture.
+fn get_pictures_tags_keywords(tag_groups: &HashMap<String, TagGroup>) -> HashMap<String, Vec<(String, Option<String>)>> {
+    let mut pictures_keywords: HashMap<String, Vec<(String, Option<String>)>> = HashMap::new();
+    for group in tag_groups.values() {
+        for tag in group.tags.values() {
+            for picture in &tag.pictures {
+                let keywords = (tag.get_keyword().to_string(), get_hierarchical_keyword(group, tag));
+                pictures_keywords.entry(picture.clone()).or_default().push(keywords);
+            }
+        }
+    }
+    for keywords in pictures_keywords.values_mut() {
+        keywords.sort();
+    }
+    pictures_keywords
+}
+
+// Keywords of the pictures whose tags changed: the keywords of their previous tags are replaced by the ones of their current tags.
+// Keywords not coming from tags are kept.
+fn get_keywords_edits(gallery: &Gallery, previous_tags: &HashMap<String, Vec<(String, Option<String>)>>) -> Vec<(String, MetadataEdit)> {
+    let current_tags = get_pictures_tags_keywords(&gallery.tag_groups);
+    let ids: HashSet<&String> = previous_tags.keys().chain(current_tags.keys()).collect();
+    ids.into_iter()
+        .filter(|id| previous_tags.get(*id) != current_tags.get(*id))
+        .filter_map(|id| {
+            let picture = gallery.datas_cache.get(id)?;
+            let (previous, current) = (
+                previous_tags.get(id).cloned().unwrap_or_default(),
+                current_tags.get(id).cloned().unwrap_or_default(),
+            );
+            let mut keywords = picture.keywords.clone();
+            let mut hierarchical_keywords = picture.hierarchical_keywords.clone();
+            keywords.retain(|keyword| !previous.iter().any(|(previous, _)| previous == keyword));
+            hierarchical_keywords.retain(|keyword| !previous.iter().any(|(_, previous)| previous.as_ref() == Some(keyword)));
+            for (keyword, hierarchical_keyword) in current {
+                if !keywords.contains(&keyword) {
+                    keywords.push(keyword);
+                }
+                if let Some(hierarchical_keyword) = hierarchical_keyword.filter(|keyword| !hierarchical_keywords.contains(keyword)) {
+                    hierarchical_keywords.push(hierarchical_keyword);
+                }
+            }
+            let edit = MetadataEdit {
+                keywords: Some(keywords),
+                hierarchical_keywords: Some(hierarchical_keywords),
+                ..Default::default()
+            };
+            Some((id.clone(), edit))
+        })
+        .collect()
+}
+
+// Tags the picture with its keywords, called when the gallery is scanned if the tags are synced to XMP.
+// Hierarchical keywords go to the group named by their first level, created if needed. Other keywords go to the existing tags with the same name,
+// or to new tags of the default keywords group.
+// The picture loses the tags whose keywords were in its previously scanned metadata and have been removed since, by another application.
+pub fn import_picture_keywords(tag_groups: &mut HashMap<String, TagGroup>, id: &str, picture: &PictureCache, previous: Option<&PictureCache>) {
+    if let Some(previous) = previous {
+        for group in tag_groups.values_mut() {
+            let group_name = group.name.clone();
+            for tag in group.tags.values_mut().filter(|tag| tag.pictures.iter().any(|picture| picture == id)) {
+                let hierarchical_keyword = format!("{}{}{}", group_name, KEYWORD_SEPARATOR, tag.name);
+                let has_keywords = |picture: &PictureCache| {
+                    picture.keywords.iter().any(|keyword| keyword.eq_ignore_ascii_case(tag.get_keyword()))
+                        || picture
+                            .hierarchical_keywords
+                            .iter()
+                            .any(|keyword| keyword.eq_ignore_ascii_case(&hierarchical_keyword))
+                };
+                if has_keywords(previous) && !has_keywords(picture) {
+                    tag.pictures.retain(|picture| picture != id);
+                }
+            }
+        }
+    }
+
+    let mut hierarchy_levels: Vec<&str> = Vec::new();
+    for keyword in &picture.hierarchical_keywords {
+        hierarchy_levels.extend(keyword.split(KEYWORD_SEPARATOR).map(|level| level.trim()));
+        if let Some((group, tag)) = keyword.split_once(KEYWORD_SEPARATOR) {
+            add_keyword_tag(tag_groups, Some(group.trim()), tag.trim(), id);
+        }
+    }
+    // Applications writing hierarchical keywords also write all their levels as flat keywords, which are not tags by themselves
+    for keyword in &picture.keywords {
+        if !hierarchy_levels.iter().any(|level| level.eq_ignore_ascii_case(keyword)) {
+            add_keyword_tag(tag_groups, None, keyword, id);
+        }
+    }
+}
+
+fn add_keyword_tag(tag_groups: &mut HashMap<String, TagGroup>, group_name: Option<&str>, tag_name: &str, id: &str) {
+    if tag_name.is_empty() || group_name == Some("") {
+        return;
+    }
+    let find_group = |tag_groups: &HashMap<String, TagGroup>, name: &str| {
+        tag_groups
+            .iter()
+            .find(|(_, group)| group.name.eq_ignore_ascii_case(name))
+            .map(|(id, _)| id.clone())
+    };
+    let find_tag = |group: &TagGroup| {
+        group
+            .tags
+            .iter()
+            .find(|(_, tag)| tag.name.eq_ignore_ascii_case(tag_name))
+            .map(|(id, _)| id.clone())
+    };
+
+    // Flat keywords are matched against the tags of all the groups, sorted by name to always pick the same
+    let existing = match group_name {
+        Some(group_name) => find_group(tag_groups, group_name).map(|group_id| {
+            let tag_id = find_tag(&tag_groups[&group_id]);
+            (group_id, tag_id)
+        }),
+        None => {
+            let mut groups: Vec<_> = tag_groups.iter().collect();
+            groups.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+            groups
+                .into_iter()
+                .find_map(|(group_id, group)| Some((group_id.clone(), Some(find_tag(group)?))))
+                .or_else(|| find_group(tag_groups, DEFAULT_KEYWORDS_GROUP).map(|group_id| (group_id, None)))
+        }
+    };
+    let (group_id, tag_id) = existing.unwrap_or_else(|| {
+        let group_id = gen_new_uid();
+        let group = TagGroup {
+            name: group_name.unwrap_or(DEFAULT_KEYWORDS_GROUP).to_string(),
+            multiple: true,
+            tags: HashMap::new(),
+        };
+        tag_groups.insert(group_id.clone(), group);
+        (group_id, None)
+    });
+
+    let group = tag_groups.get_mut(&group_id).unwrap();
+    // A picture can't get a second tag of an exclusive group
+    let has_other_tag = group
+        .tags
+        .iter()
+        .any(|(other_id, tag)| Some(other_id) != tag_id.as_ref() && tag.pictures.iter().any(|picture| picture == id));
+    if !group.multiple && has_other_tag {
+        return;
+    }
+    let tag = group.tags.entry(tag_id.unwrap_or_else(gen_new_uid)).or_insert_with(|| Tag {
+        name: tag_name.to_string(),
+        color: DEFAULT_TAG_COLOR.to_string(),
+        pictures: Vec::new(),
+    });
+    if !tag.pictures.iter().any(|picture| picture == id) {
+        tag.pictures.push(id.to_string());
+    }
+}
+
+fn get_group<'a>(tag_groups: &'a mut HashMap<String, TagGroup>, group_id: &str) -> Result<&'a mut TagGroup, TagError> {
+    tag_groups.get_mut(group_id).ok_or_else(|| TagError::GroupNotFound(group_id.to_string()))
+}
+
+fn get_tag<'a>(group: &'a mut TagGroup, tag_id: &str) -> Result<&'a mut Tag, TagError> {
+    group.tags.get_mut(tag_id).ok_or_else(|| TagError::TagNotFound(tag_id.to_string()))
+}
+
+// Group names must be unique in the gallery, case insensitively. They are the first level of the hierarchical keywords, so they can't contain its separator.
+fn check_group_name(tag_groups: &HashMap<String, TagGroup>, name: &str, group_id: Option<&str>) -> Result<String, TagError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(TagError::EmptyName);
+    }
+    if name.contains(KEYWORD_SEPARATOR) {
+        return Err(TagError::SeparatorInGroupName);
+    }
+    if tag_groups
+        .iter()
+        .any(|(id, group)| Some(id.as_str()) != group_id && group.name.eq_ignore_ascii_case(name))
+    {
+        return Err(TagError::NameAlreadyUsed(name.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+// Tag names must be unique in their group, case insensitively.
+fn check_tag_name(group: &TagGroup, name: &str, tag_id: Option<&str>) -> Result<String, TagError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(TagError::EmptyName);
+    }
+    if group.has_tag_named(name, tag_id) {
+        return Err(TagError::NameAlreadyUsed(name.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+fn check_color(color: &str) -> Result<String, TagError> {
+    let color = color.trim();
+    if !is_valid_tag_color(color) {
+        return Err(TagError::InvalidColor(color.to_string()));
+    }
+    Ok(color.to_lowercase())
+}
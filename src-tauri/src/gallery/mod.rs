@@ -2,4 +2,8 @@ pub mod gallery_data;
 pub mod windows_galleries;
 pub mod gallery_cache;
 pub mod gallery_clusters;
-pub mod gallery_tags;
\ No newline at end of file
+pub mod gallery_tags;
+pub mod gallery_metadata;
+pub mod gallery_ratings;
+pub mod gallery_geotag;
+pub mod gallery_export;
@@ -1,9 +1,14 @@
+use std::path::PathBuf;
 use std::sync::{Mutex, MutexGuard};
+use std::thread;
 
 use log::info;
-use tauri::{AppHandle, Window, Wry};
+use tauri::{AppHandle, Manager, Window, Wry};
 
+use crate::app_data::AppDataState;
 use crate::header::window::new_window;
+use crate::utils::thumbnails::{enforce_thumbnails_cache_limit, get_thumbnails_dir};
+use crate::utils::thumbnails_batch::ThumbnailsBatchState;
 
 use super::gallery_data::Gallery;
 
@@ -17,6 +22,7 @@ pub struct WindowGallery {
     pub window_label: String,
     pub path: String,
     pub gallery: Gallery,
+    pub thumbnails_dir: PathBuf, // Depends on the gallery settings
 }
 
 impl WindowsGalleriesState {
@@ -37,14 +43,28 @@ impl WindowsGalleriesState {
     // Called in order to open a new gallery window
     pub fn open_from_path(&self, app_handle: &AppHandle<Wry>, path: String) {
         let label = self.get_new_unique_label();
+        let gallery = Gallery::load(&path);
+        let resume_thumbnails_batch = gallery.thumbnails_batch.is_some();
 
         self.galleries.lock().unwrap().push(WindowGallery {
             window_label: label.clone(),
+            thumbnails_dir: get_thumbnails_dir(app_handle, &path, &gallery),
             path: path.clone(),
-            gallery: Gallery::load(&path),
+            gallery,
         });
 
-        new_window(app_handle, label);
+        let app = app_handle.clone();
+        thread::spawn(move || {
+            let limit = app.state::<AppDataState>().data().settings.thumbnails_cache_limit;
+            enforce_thumbnails_cache_limit(&app, limit);
+        });
+
+        new_window(app_handle, label.clone());
+
+        // Resuming the thumbnails batch generation that was interrupted when the gallery was closed
+        if resume_thumbnails_batch {
+            app_handle.state::<ThumbnailsBatchState>().start(app_handle, label);
+        }
     }
     // Called when a gallery window is closed
     pub fn on_close(&self, label: String) {
@@ -5,7 +5,9 @@ use tauri::{AppHandle, State, Window, Wry};
 use tauri::{CustomMenuItem, Menu, MenuItem, Submenu};
 
 use crate::gallery::gallery_cache::update_gallery_cache;
-use crate::gallery::windows_galleries::WindowsGalleriesState;
+use crate::gallery::windows_galleries::{WindowGallery, WindowsGalleriesState};
+use crate::utils::thumbnails::{clean_thumbnails, remove_outdated_thumbnails};
+use crate::utils::thumbnails_batch::{gen_thumbnails_batch, ThumbnailsBatchState};
 use crate::utils::translator::TranslatorState;
 
 use super::window::{close_window, quit_app};
@@ -39,7 +41,8 @@ pub fn setup_menubar(app_name: String, t: &TranslatorState) -> Menu {
             tr(t, "menu-bar-file-recent-galleries"),
         ))
         .add_item(CustomMenuItem::new("new_gallery".to_string(), tr(t, "menu-bar-file-new-gallery")).accelerator("Cmd+N"))
-        .add_native_item(MenuItem::Separator);
+        .add_native_item(MenuItem::Separator)
+        .add_item(CustomMenuItem::new("export".to_string(), tr(t, "menu-bar-file-export")).accelerator("Cmd+E"));
 
     let edit_menu = Menu::new()
         .add_native_item(MenuItem::Undo)
@@ -52,7 +55,19 @@ pub fn setup_menubar(app_name: String, t: &TranslatorState) -> Menu {
 
     let tools_menu = Menu::new()
         .add_item(CustomMenuItem::new("update_gallery".to_string(), tr(t, "menu-bar-tools-update-gallery")))
-        .add_item(CustomMenuItem::new("edit_exif".to_string(), tr(t, "menu-bar-tools-edit-exif")));
+        .add_item(CustomMenuItem::new("edit_exif".to_string(), tr(t, "menu-bar-tools-edit-exif")))
+        .add_item(CustomMenuItem::new("shift_dates".to_string(), tr(t, "menu-bar-tools-shift-dates")))
+        .add_item(CustomMenuItem::new("geotag".to_string(), tr(t, "menu-bar-tools-geotag")))
+        .add_native_item(MenuItem::Separator)
+        .add_item(CustomMenuItem::new("gen_thumbnails".to_string(), tr(t, "menu-bar-tools-gen-thumbnails")))
+        .add_item(CustomMenuItem::new(
+            "regen_thumbnails".to_string(),
+            tr(t, "menu-bar-tools-regen-thumbnails"),
+        ))
+        .add_item(CustomMenuItem::new(
+            "clean_thumbnails".to_string(),
+            tr(t, "menu-bar-tools-clean-thumbnails"),
+        ));
 
     let window_menu = Menu::new()
         .add_native_item(MenuItem::Minimize)
@@ -90,5 +105,65 @@ pub fn menu_close_window(window: Window<Wry>, app: AppHandle<Wry>) {
 pub async fn menu_update_gallery(window: Window<Wry>, galleries_state: State<'_, WindowsGalleriesState>) -> Result<(), ()> {
     let data = update_gallery_cache(&window, &galleries_state);
     window.emit("gallery-cache-changed", data).unwrap();
+    // Ratings and tags of new pictures might have been read from their metadata
+    let (ratings, tag_groups) = {
+        let galleries = galleries_state.get_galleries();
+        let gallery = WindowGallery::get(&galleries, &window);
+        (gallery.gallery.ratings.clone(), gallery.gallery.tag_groups.clone())
+    };
+    window.emit("gallery-ratings-changed", ratings).unwrap();
+    window.emit("gallery-tags-changed", tag_groups).unwrap();
+    Ok(())
+}
+
+// The export tool is part of the frontend, it exports the selected pictures.
+#[tauri::command]
+pub fn menu_export(window: Window<Wry>) {
+    let _ = window.emit("menu-export", ());
+}
+
+// The metadata editor is part of the frontend, it edits the selected pictures.
+#[tauri::command]
+pub fn menu_edit_exif(window: Window<Wry>) {
+    let _ = window.emit("menu-edit-metadata", ());
+}
+#[tauri::command]
+pub fn menu_shift_dates(window: Window<Wry>) {
+    let _ = window.emit("menu-shift-dates", ());
+}
+#[tauri::command]
+pub fn menu_geotag(window: Window<Wry>) {
+    let _ = window.emit("menu-geotag", ());
+}
+
+#[tauri::command]
+pub fn menu_gen_thumbnails(
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    galleries_state: State<WindowsGalleriesState>,
+    batch_state: State<ThumbnailsBatchState>,
+) {
+    gen_thumbnails_batch(app, window, galleries_state, batch_state, Vec::new());
+}
+
+// Regenerates the thumbnails after a change of the rendering settings.
+#[tauri::command]
+pub fn menu_regen_thumbnails(
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    galleries_state: State<WindowsGalleriesState>,
+    batch_state: State<ThumbnailsBatchState>,
+) {
+    {
+        let mut galleries = galleries_state.get_galleries();
+        remove_outdated_thumbnails(WindowGallery::get_mut(&mut galleries, &window));
+    }
+    gen_thumbnails_batch(app, window, galleries_state, batch_state, Vec::new());
+}
+
+#[tauri::command]
+pub async fn menu_clean_thumbnails(window: Window<Wry>, galleries_state: State<'_, WindowsGalleriesState>) -> Result<(), ()> {
+    let result = clean_thumbnails(window.clone(), galleries_state);
+    window.emit("thumbnails-cleaned", result).unwrap();
     Ok(())
 }
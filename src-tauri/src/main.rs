@@ -1,28 +1,42 @@
 #![cfg_attr(all(not(debug_assertions), target_os = "windows"), windows_subsystem = "windows")]
 
-use std::fs::read;
-use std::path::PathBuf;
-
 use log::info;
-use tauri::{http::ResponseBuilder, Manager};
+use tauri::Manager;
 use tauri_plugin_window_state::StateFlags;
-use url::Url;
 
 use app_data::{AppData, AppDataState};
 use gallery::windows_galleries::{get_gallery_path, WindowsGalleriesState};
 #[cfg(target_os = "macos")]
 use header::macos::WindowMacosExt;
-use header::menubar::{menu_close_window, menu_quit, menu_update_gallery};
+use header::menubar::{
+    menu_clean_thumbnails, menu_close_window, menu_edit_exif, menu_export, menu_gen_thumbnails, menu_geotag, menu_quit, menu_regen_thumbnails,
+    menu_shift_dates, menu_update_gallery,
+};
 use utils::commands::{greet, open_devtools};
 use utils::logger::{get_logger_plugin, log_from_front};
-use utils::thumbnails::{gen_image_thumbnail, get_existing_thumbnail, get_image_dimensions};
+use utils::previews::{request_preview, PreviewsState};
+use utils::protocol::handle_reqimg_request;
+use utils::thumbnails::{clean_thumbnails, get_image_dimensions};
+use utils::thumbnails_batch::{cancel_thumbnails_batch, gen_thumbnails_batch, ThumbnailsBatchState};
+use utils::thumbnails_queue::{cancel_thumbnail, request_thumbnail, update_thumbnail_priority, ThumbnailsQueueState};
+use utils::tiles::request_tiles;
 use utils::translator::TranslatorState;
 
 use crate::app_data::{get_settings, set_settings};
 use crate::gallery::gallery_cache::{get_gallery_datas_cache, get_gallery_paths_cache};
-use crate::gallery::gallery_data::{get_gallery_data, get_gallery_settings, set_gallery_data, set_gallery_settings};
-use crate::gallery::windows_galleries::WindowGallery;
+use crate::gallery::gallery_data::{
+    get_gallery_data, get_gallery_hierarchy, get_gallery_settings, set_gallery_data, set_gallery_hierarchy, set_gallery_settings,
+};
+use crate::gallery::gallery_export::{export_pictures, pick_export_dir};
+use crate::gallery::gallery_geotag::{geotag_pictures, pick_track_files, preview_geotag};
+use crate::gallery::gallery_metadata::{edit_pictures_metadata, get_picture_metadata, preview_date_shift, shift_pictures_dates};
+use crate::gallery::gallery_ratings::{get_gallery_ratings, set_pictures_rating};
+use crate::gallery::gallery_tags::{
+    create_tag, create_tag_group, delete_tag, delete_tag_group, get_gallery_tag_groups, merge_tag_groups, merge_tags, recolor_tag, rename_tag,
+    rename_tag_group, set_tag_group_multiple, tag_pictures, untag_pictures,
+};
 use crate::header::window::close_window;
+use crate::utils::geocoding::init_geocoding;
 use crate::utils::translator::{get_available_locales, get_system_locale, get_translation_file, Translator};
 
 mod app_data;
@@ -44,6 +58,8 @@ fn main() {
             let translator = app.state::<TranslatorState>();
             *translator.translator.lock().unwrap() = Some(Translator::new(&(app.app_handle()), data.data().settings.language.clone()));
 
+            init_geocoding(&app.app_handle());
+
             let galleries = app.state::<WindowsGalleriesState>();
 
             #[cfg(target_os = "macos")]
@@ -63,6 +79,8 @@ fn main() {
                 info!("🚩 Window {} destroyed", event.window().label());
                 let app_handle = event.window().app_handle();
 
+                app_handle.state::<ThumbnailsQueueState>().cancel_window(event.window().label());
+                app_handle.state::<ThumbnailsBatchState>().stop(event.window().label());
                 let galleries = app_handle.state::<WindowsGalleriesState>();
                 galleries.on_close(event.window().label().into());
 
@@ -91,51 +109,43 @@ fn main() {
                         let _ = menu_update_gallery(event.window().clone(), event.window().state::<WindowsGalleriesState>()).await;
                     });
                 }
-                _ => {}
-            }
-        })
-        .register_uri_scheme_protocol("reqimg", move |app, request| {
-            let res_not_found = ResponseBuilder::new().status(404).body(Vec::new());
-
-            let url = Url::parse(request.uri()).unwrap();
-
-            let label = url.query_pairs().find(|(key, _)| key == "window").unwrap().1.to_string();
-            let window = app.get_window(&label).expect("window not found");
-
-            let galleries_state = app.state::<WindowsGalleriesState>();
-            let galleries = galleries_state.get_galleries();
-            let gallery = WindowGallery::get(&galleries, &window);
-
-            return match url.path() {
-                "/get-thumbnail" => {
-                    // The frontend must make sure the thumbnail exists before by calling the command gen_image_thumbnail.
-
-                    let id = url.query_pairs().find(|(key, _)| key == "id").unwrap().1.to_string();
-
-                    if let Some(data) = get_existing_thumbnail(&gallery.path, &id) {
-                        ResponseBuilder::new().mimetype("image/png").body(data)
-                    } else {
-                        info!("🖼️ Can't read thumbnail {}", id);
-                        res_not_found
-                    }
+                "export" => menu_export(event.window().clone()),
+                "edit_exif" => menu_edit_exif(event.window().clone()),
+                "shift_dates" => menu_shift_dates(event.window().clone()),
+                "geotag" => menu_geotag(event.window().clone()),
+                "gen_thumbnails" => {
+                    let window = event.window();
+                    menu_gen_thumbnails(
+                        window.app_handle(),
+                        window.clone(),
+                        window.state::<WindowsGalleriesState>(),
+                        window.state::<ThumbnailsBatchState>(),
+                    );
                 }
-                "/get-image" => {
-                    let id = url.query_pairs().find(|(key, _)| key == "id").unwrap().1.to_string();
-                    let path = PathBuf::from(&gallery.path).join(gallery.gallery.datas_cache.get(&id).unwrap().get_path());
-
-                    if let Ok(data) = read(path) {
-                        ResponseBuilder::new().mimetype("image").body(data)
-                    } else {
-                        info!("🖼️ Can't read image {}", id);
-                        res_not_found
-                    }
+                "regen_thumbnails" => {
+                    let window = event.window();
+                    menu_regen_thumbnails(
+                        window.app_handle(),
+                        window.clone(),
+                        window.state::<WindowsGalleriesState>(),
+                        window.state::<ThumbnailsBatchState>(),
+                    );
+                }
+                "clean_thumbnails" => {
+                    tauri::async_runtime::spawn(async move {
+                        let _ = menu_clean_thumbnails(event.window().clone(), event.window().state::<WindowsGalleriesState>()).await;
+                    });
                 }
-                _ => res_not_found,
-            };
+                _ => {}
+            }
         })
+        .register_uri_scheme_protocol("reqimg", handle_reqimg_request)
         .manage(TranslatorState::default())
         .manage(AppDataState::default())
         .manage(WindowsGalleriesState::default())
+        .manage(ThumbnailsQueueState::default())
+        .manage(ThumbnailsBatchState::default())
+        .manage(PreviewsState::default())
         .plugin(tauri_plugin_context_menu::init())
         .plugin(get_logger_plugin())
         .plugin(
@@ -156,6 +166,13 @@ fn main() {
             menu_quit,
             menu_close_window,
             menu_update_gallery,
+            menu_export,
+            menu_edit_exif,
+            menu_shift_dates,
+            menu_geotag,
+            menu_gen_thumbnails,
+            menu_regen_thumbnails,
+            menu_clean_thumbnails,
             // Gallery
             get_gallery_path,
             get_gallery_datas_cache,
@@ -164,9 +181,42 @@ fn main() {
             set_gallery_data,
             get_gallery_settings,
             set_gallery_settings,
+            get_gallery_hierarchy,
+            set_gallery_hierarchy,
+            edit_pictures_metadata,
+            get_picture_metadata,
+            preview_date_shift,
+            shift_pictures_dates,
+            pick_track_files,
+            preview_geotag,
+            geotag_pictures,
+            pick_export_dir,
+            export_pictures,
+            get_gallery_ratings,
+            set_pictures_rating,
+            get_gallery_tag_groups,
+            create_tag_group,
+            rename_tag_group,
+            set_tag_group_multiple,
+            merge_tag_groups,
+            delete_tag_group,
+            create_tag,
+            rename_tag,
+            recolor_tag,
+            merge_tags,
+            delete_tag,
+            tag_pictures,
+            untag_pictures,
             // Images
-            gen_image_thumbnail,
+            request_thumbnail,
+            update_thumbnail_priority,
+            request_preview,
+            request_tiles,
+            cancel_thumbnail,
+            gen_thumbnails_batch,
+            cancel_thumbnails_batch,
             get_image_dimensions,
+            clean_thumbnails,
             // Other commands
             greet,
             open_devtools
@@ -0,0 +1,140 @@
+use std::io::{BufRead, Seek};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use image::codecs::jpeg::JpegDecoder;
+use image::codecs::png::PngDecoder;
+use image::io::Reader as ImageReader;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageResult};
+use lcms2::{ColorSpaceSignature, Flags, InfoType, Intent, Locale, PixelFormat, Profile, Transform};
+use log::warn;
+
+// Pictures are converted to sRGB, the only color space all webviews render the same way.
+// Pictures without an embedded profile are considered to already be in sRGB.
+
+static SRGB_ICC: OnceLock<Vec<u8>> = OnceLock::new();
+
+fn get_srgb_icc() -> &'static [u8] {
+    SRGB_ICC.get_or_init(|| Profile::new_srgb().icc().unwrap_or_default())
+}
+
+// Decodes an image and returns its embedded ICC profile. Profiles are only read from JPEG and PNG files.
+pub fn decode_with_icc<R: BufRead + Seek>(reader: ImageReader<R>) -> ImageResult<(DynamicImage, Option<Vec<u8>>)> {
+    match reader.format() {
+        Some(ImageFormat::Jpeg) => {
+            let mut decoder = JpegDecoder::new(reader.into_inner())?;
+            let icc = decoder.icc_profile();
+            Ok((DynamicImage::from_decoder(decoder)?, icc))
+        }
+        Some(ImageFormat::Png) => {
+            let mut decoder = PngDecoder::new(reader.into_inner())?;
+            let icc = decoder.icc_profile();
+            Ok((DynamicImage::from_decoder(decoder)?, icc))
+        }
+        _ => Ok((reader.decode()?, None)),
+    }
+}
+
+// Reads the embedded ICC profile of an image without decoding its pixels.
+pub fn read_icc_profile(path: &Path) -> Option<Vec<u8>> {
+    let reader = ImageReader::open(path).ok()?.with_guessed_format().ok()?;
+    match reader.format()? {
+        ImageFormat::Jpeg => JpegDecoder::new(reader.into_inner()).ok()?.icc_profile(),
+        ImageFormat::Png => PngDecoder::new(reader.into_inner()).ok()?.icc_profile(),
+        _ => None,
+    }
+}
+
+// Converts the pixels from the ICC profile to sRGB. Invalid and non RGB profiles are ignored.
+pub fn convert_to_srgb(img: DynamicImage, icc: Option<&[u8]>) -> DynamicImage {
+    let Some(profile) = icc.and_then(|icc| Profile::new_icc(icc).ok()) else {
+        return img;
+    };
+    if profile.color_space() != ColorSpaceSignature::RgbData || is_srgb(&profile) {
+        return img;
+    }
+    let srgb = Profile::new_srgb();
+
+    if img.color().has_alpha() {
+        let mut pixels = img.into_rgba8();
+        match Transform::<u8, u8>::new_flags(
+            &profile,
+            PixelFormat::RGBA_8,
+            &srgb,
+            PixelFormat::RGBA_8,
+            Intent::Perceptual,
+            Flags::COPY_ALPHA,
+        ) {
+            Ok(transform) => transform.transform_in_place(&mut pixels),
+            Err(e) => warn!("Unable to convert picture to sRGB: {}", e),
+        }
+        DynamicImage::ImageRgba8(pixels)
+    } else {
+        let mut pixels = img.into_rgb8();
+        match Transform::<u8, u8>::new(&profile, PixelFormat::RGB_8, &srgb, PixelFormat::RGB_8, Intent::Perceptual) {
+            Ok(transform) => transform.transform_in_place(&mut pixels),
+            Err(e) => warn!("Unable to convert picture to sRGB: {}", e),
+        }
+        DynamicImage::ImageRgb8(pixels)
+    }
+}
+
+// Most cameras and editors embed a sRGB profile, converting it would only waste time.
+fn is_srgb(profile: &Profile) -> bool {
+    profile
+        .info(InfoType::Description, Locale::none())
+        .map_or(false, |description| description.starts_with("sRGB"))
+}
+
+// Inserts the sRGB profile in an APP2 segment, right after the JFIF header.
+pub fn embed_srgb_in_jpeg(data: Vec<u8>) -> Vec<u8> {
+    let icc = get_srgb_icc();
+    if icc.is_empty() || data.len() < 4 || data[0..2] != [0xFF, 0xD8] {
+        return data;
+    }
+    let mut pos = 2;
+    if data.len() >= 6 && data[2..4] == [0xFF, 0xE0] {
+        pos += 2 + u16::from_be_bytes([data[4], data[5]]) as usize;
+    }
+
+    let mut segment = vec![0xFF, 0xE2];
+    segment.extend_from_slice(&((2 + 14 + icc.len()) as u16).to_be_bytes());
+    segment.extend_from_slice(b"ICC_PROFILE\0");
+    segment.extend_from_slice(&[1, 1]); // Chunk 1 of 1
+    segment.extend_from_slice(icc);
+
+    let mut result = Vec::with_capacity(data.len() + segment.len());
+    result.extend_from_slice(&data[..pos]);
+    result.extend_from_slice(&segment);
+    result.extend_from_slice(&data[pos..]);
+    result
+}
+
+// Converts a simple lossy WebP file to the extended format in order to add an ICCP chunk.
+pub fn embed_srgb_in_webp(data: Vec<u8>, width: u32, height: u32) -> Vec<u8> {
+    let icc = get_srgb_icc();
+    if icc.is_empty() || data.len() < 16 || &data[0..4] != b"RIFF" || &data[8..12] != b"WEBP" || &data[12..16] != b"VP8 " {
+        return data;
+    }
+
+    let mut result = Vec::with_capacity(data.len() + icc.len() + 32);
+    result.extend_from_slice(b"RIFF\0\0\0\0WEBP");
+    // VP8X chunk: flags with ICC bit, reserved bytes, then canvas dimensions minus one on 24 bits
+    result.extend_from_slice(b"VP8X");
+    result.extend_from_slice(&10u32.to_le_bytes());
+    result.extend_from_slice(&[0x20, 0, 0, 0]);
+    result.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+    result.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+    // ICCP chunk, padded to an even size
+    result.extend_from_slice(b"ICCP");
+    result.extend_from_slice(&(icc.len() as u32).to_le_bytes());
+    result.extend_from_slice(icc);
+    if icc.len() % 2 == 1 {
+        result.push(0);
+    }
+    result.extend_from_slice(&data[12..]);
+
+    let riff_size = (result.len() - 8) as u32;
+    result[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    result
+}
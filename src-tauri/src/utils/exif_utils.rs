@@ -1,13 +1,18 @@
 use std::{
     ffi::OsString,
-    path::PathBuf,
+    fs,
+    path::{Path, PathBuf},
     sync::atomic::{AtomicU64, Ordering},
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use pm_common::gallery_cache::Orientation;
+use pm_common::gallery_cache::{CaptureDate, DateSource, Orientation};
+use pm_common::metadata::{HomeZone, MetadataEdit, MetadataGroup, MetadataStrip, MetadataTag};
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time};
 
 use crate::gallery::gallery_cache::PictureCache;
+use crate::utils::geocoding::reverse_geocode;
+use crate::utils::thumbnails::get_dominant_color;
 
 pub struct ExifFile {
     path: OsString,
@@ -50,6 +55,48 @@ impl ExifFile {
     pub fn get_date(&self) -> Option<String> {
         self.meta.get_tag_string("Exif.Photo.DateTimeOriginal").ok()
     }
+    // Reads the capture date from the metadata, then falls back to the file name and the file modification time.
+    pub fn get_capture_date(&self) -> Option<CaptureDate> {
+        let exif_date = |date_tag: &str, offset_tag: &str, source: DateSource| {
+            let date = parse_exif_date(&self.get_tag_string(date_tag)?)?;
+            Some(CaptureDate {
+                local: date.assume_utc().unix_timestamp(),
+                offset: self.get_tag_string(offset_tag).and_then(|offset| parse_exif_timezone(&offset)),
+                source,
+            })
+        };
+        exif_date(
+            "Exif.Photo.DateTimeOriginal",
+            "Exif.Photo.OffsetTimeOriginal",
+            DateSource::DateTimeOriginal,
+        )
+        .or_else(|| exif_date("Exif.Photo.DateTimeDigitized", "Exif.Photo.OffsetTimeDigitized", DateSource::CreateDate))
+        .or_else(|| {
+            let (date, offset) = parse_xmp_date(&self.get_tag_string("Xmp.photoshop.DateCreated")?)?;
+            Some(CaptureDate {
+                local: date.assume_utc().unix_timestamp(),
+                offset,
+                source: DateSource::XmpDateCreated,
+            })
+        })
+        .or_else(|| {
+            let date = parse_file_name_date(&Path::new(&self.path).file_stem()?.to_string_lossy())?;
+            Some(CaptureDate {
+                local: date.assume_utc().unix_timestamp(),
+                offset: None,
+                source: DateSource::FileName,
+            })
+        })
+        .or_else(|| {
+            // The modification time is an instant, its timezone is UTC.
+            let modified = OffsetDateTime::from(fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok()?);
+            Some(CaptureDate {
+                local: modified.unix_timestamp(),
+                offset: Some(0),
+                source: DateSource::FileModified,
+            })
+        })
+    }
     pub fn get_location(&self) -> Option<(f64, f64, f64)> {
         if let Some(gps_info) = self.meta.get_gps_info() {
             Some((gps_info.latitude, gps_info.longitude, gps_info.altitude))
@@ -60,6 +107,71 @@ impl ExifFile {
     pub fn get_camera(&self) -> Option<String> {
         self.meta.get_tag_string("Exif.Image.Model").ok()
     }
+    pub fn get_camera_make(&self) -> Option<String> {
+        self.meta.get_tag_string("Exif.Image.Make").ok()
+    }
+    // Title, description and keywords are read from XMP first, then from the legacy IPTC fields.
+    pub fn get_title(&self) -> Option<String> {
+        self.get_lang_alt("Xmp.dc.title")
+            .or_else(|| self.get_tag_string("Iptc.Application2.ObjectName"))
+    }
+    pub fn get_description(&self) -> Option<String> {
+        self.get_lang_alt("Xmp.dc.description")
+            .or_else(|| self.get_tag_string("Exif.Image.ImageDescription"))
+            .or_else(|| self.get_tag_string("Iptc.Application2.Caption"))
+    }
+    pub fn get_keywords(&self) -> Vec<String> {
+        let tag = if self.meta.has_tag("Xmp.dc.subject") {
+            "Xmp.dc.subject"
+        } else {
+            "Iptc.Application2.Keywords"
+        };
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in self.meta.get_tag_multiple_strings(tag).unwrap_or_default() {
+            let keyword = keyword.trim();
+            if !keyword.is_empty() && !keywords.iter().any(|k| k == keyword) {
+                keywords.push(keyword.to_string());
+            }
+        }
+        keywords
+    }
+    // Lightroom hierarchical keywords, like People|Family|Alice.
+    pub fn get_hierarchical_keywords(&self) -> Vec<String> {
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in self.meta.get_tag_multiple_strings("Xmp.lr.hierarchicalSubject").unwrap_or_default() {
+            let keyword = keyword.trim();
+            if !keyword.is_empty() && !keywords.iter().any(|k| k == keyword) {
+                keywords.push(keyword.to_string());
+            }
+        }
+        keywords
+    }
+    // From 1 to 5 stars, -1 meaning rejected. Unrated pictures have no rating.
+    pub fn get_rating(&self) -> Option<i32> {
+        self.get_tag_string("Xmp.xmp.Rating")
+            .and_then(|rating| rating.parse::<f64>().ok())
+            .map(|rating| rating.round() as i32)
+            .or_else(|| self.get_tag_numeric("Exif.Image.Rating"))
+            .filter(|rating| *rating != 0)
+    }
+    pub fn get_label(&self) -> Option<String> {
+        self.get_tag_string("Xmp.xmp.Label")
+    }
+    pub fn get_copyright(&self) -> Option<String> {
+        self.meta
+            .get_tag_string("Exif.Image.Copyright")
+            .ok()
+            .or_else(|| self.get_lang_alt("Xmp.dc.rights"))
+    }
+    // XMP language alternatives are read as `lang="x-default" value`, only the value is kept.
+    fn get_lang_alt(&self, tag: &str) -> Option<String> {
+        let value = self.meta.get_tag_string(tag).ok()?;
+        let value = match value.strip_prefix("lang=\"") {
+            Some(value) => value.split_once("\" ").map_or("", |(_, value)| value).to_string(),
+            None => value,
+        };
+        Some(value).filter(|value| !value.is_empty())
+    }
     pub fn get_orientation(&self) -> Orientation {
         Orientation::from_rexiv2(self.meta.get_orientation())
     }
@@ -76,11 +188,204 @@ impl ExifFile {
     pub fn get_f_number(&self) -> Option<f64> {
         self.meta.get_fnumber()
     }
+    pub fn get_lens(&self) -> Option<String> {
+        self.get_tag_string("Exif.Photo.LensModel")
+            .or_else(|| self.get_tag_string("Xmp.aux.Lens"))
+    }
+    pub fn get_focal_length_35mm(&self) -> Option<u32> {
+        self.get_tag_numeric("Exif.Photo.FocalLengthIn35mmFilm")
+            .map(|focal_length| focal_length as u32)
+            .filter(|focal_length| *focal_length > 0) // 0 means unknown
+    }
+    pub fn get_exposure_bias(&self) -> Option<f64> {
+        self.get_tag_float("Exif.Photo.ExposureBiasValue")
+    }
+    pub fn get_flash(&self) -> Option<u16> {
+        self.get_tag_numeric("Exif.Photo.Flash").map(|flash| flash as u16)
+    }
+    pub fn get_metering_mode(&self) -> Option<u16> {
+        self.get_tag_numeric("Exif.Photo.MeteringMode").map(|mode| mode as u16)
+    }
+    pub fn get_white_balance(&self) -> Option<u16> {
+        self.get_tag_numeric("Exif.Photo.WhiteBalance").map(|white_balance| white_balance as u16)
+    }
+    pub fn get_software(&self) -> Option<String> {
+        self.get_tag_string("Exif.Image.Software")
+            .or_else(|| self.get_tag_string("Xmp.xmp.CreatorTool"))
+    }
+    pub fn get_offset_time_original(&self) -> Option<String> {
+        self.get_tag_string("Exif.Photo.OffsetTimeOriginal")
+    }
+    pub fn get_offset_time(&self) -> Option<String> {
+        self.get_tag_string("Exif.Photo.OffsetTime")
+    }
+    pub fn get_gps_direction(&self) -> Option<f64> {
+        self.get_tag_float("Exif.GPSInfo.GPSImgDirection")
+    }
+    pub fn get_file_size(&self) -> u64 {
+        fs::metadata(&self.path).map_or(0, |metadata| metadata.len())
+    }
+    pub fn get_file_format(&self) -> Option<String> {
+        Path::new(&self.path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_uppercase())
+    }
+    // Tags getters returning None for missing or empty tags.
+    fn get_tag_string(&self, tag: &str) -> Option<String> {
+        self.meta
+            .get_tag_string(tag)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+    fn get_tag_numeric(&self, tag: &str) -> Option<i32> {
+        self.meta.has_tag(tag).then(|| self.meta.get_tag_numeric(tag))
+    }
+    fn get_tag_float(&self, tag: &str) -> Option<f64> {
+        self.meta
+            .get_tag_rational(tag)
+            .filter(|value| *value.denom() != 0)
+            .map(|value| *value.numer() as f64 / *value.denom() as f64)
+    }
+    // Dominant color of the smallest embedded preview, which is cheap to decode.
+    pub fn get_color(&self) -> Option<(u8, u8, u8)> {
+        let preview = self
+            .meta
+            .get_preview_images()?
+            .into_iter()
+            .min_by_key(|preview| preview.get_width() * preview.get_height())?;
+        let img = image::load_from_memory(&preview.get_data().ok()?).ok()?;
+        get_dominant_color(img.to_rgb8().as_raw(), 3)
+    }
     // Does not takes into account orientation
     pub fn get_dimensions(&self) -> (u32, u32) {
         (self.meta.get_pixel_width() as u32, self.meta.get_pixel_height() as u32)
     }
 
+    // Applies the metadata changes, then saves them to the file. The edit must have been validated before.
+    pub fn apply_edit(&self, edit: &MetadataEdit) -> Result<(), rexiv2::Rexiv2Error> {
+        if let Some(date) = &edit.date {
+            self.set_or_clear_tag("Exif.Photo.DateTimeOriginal", date)?;
+        }
+        if let Some(offset_time) = &edit.offset_time {
+            self.set_or_clear_tag("Exif.Photo.OffsetTimeOriginal", offset_time)?;
+        }
+        if let Some(seconds) = edit.date_shift {
+            self.shift_dates(seconds, edit.offset_time.as_deref())?;
+        }
+        if edit.remove_location {
+            self.meta.delete_gps_info();
+        } else if let Some((latitude, longitude, altitude)) = edit.location {
+            self.meta.set_gps_info(&rexiv2::GpsInfo {
+                latitude,
+                longitude,
+                altitude: altitude.unwrap_or(0.0),
+            })?;
+            // rexiv2 always writes an altitude, which must not be set to 0 when unknown
+            if altitude.is_none() {
+                self.meta.clear_tag("Exif.GPSInfo.GPSAltitude");
+                self.meta.clear_tag("Exif.GPSInfo.GPSAltitudeRef");
+            }
+        }
+        if let Some(make) = &edit.camera_make {
+            self.set_or_clear_tag("Exif.Image.Make", make)?;
+        }
+        if let Some(model) = &edit.camera_model {
+            self.set_or_clear_tag("Exif.Image.Model", model)?;
+        }
+        // Title, description and keywords are also written to IPTC, still read by many applications.
+        let iptc = self.meta.supports_iptc();
+        if iptc && (edit.title.is_some() || edit.description.is_some() || edit.keywords.is_some()) {
+            // ESC % G: the IPTC values are UTF-8
+            self.meta.set_tag_string("Iptc.Envelope.CharacterSet", "\u{1b}%G")?;
+        }
+        if let Some(title) = &edit.title {
+            self.set_or_clear_tag("Xmp.dc.title", title)?;
+            if iptc {
+                self.set_or_clear_tag("Iptc.Application2.ObjectName", title)?;
+            }
+        }
+        // Description and copyright are written both in EXIF and XMP, as applications read one or the other.
+        if let Some(description) = &edit.description {
+            self.set_or_clear_tag("Exif.Image.ImageDescription", description)?;
+            self.set_or_clear_tag("Xmp.dc.description", description)?;
+            if iptc {
+                self.set_or_clear_tag("Iptc.Application2.Caption", description)?;
+            }
+        }
+        if let Some(keywords) = &edit.keywords {
+            let keywords: Vec<&str> = keywords.iter().map(|keyword| keyword.as_str()).collect();
+            self.set_or_clear_multiple_tag("Xmp.dc.subject", &keywords)?;
+            if iptc {
+                self.set_or_clear_multiple_tag("Iptc.Application2.Keywords", &keywords)?;
+            }
+        }
+        if let Some(keywords) = &edit.hierarchical_keywords {
+            let keywords: Vec<&str> = keywords.iter().map(|keyword| keyword.as_str()).collect();
+            self.set_or_clear_multiple_tag("Xmp.lr.hierarchicalSubject", &keywords)?;
+        }
+        if let Some(label) = &edit.label {
+            self.set_or_clear_tag("Xmp.xmp.Label", label)?;
+        }
+        if let Some(rating) = edit.rating {
+            let rating = if rating == 0 { String::new() } else { rating.to_string() };
+            self.set_or_clear_tag("Xmp.xmp.Rating", &rating)?;
+            // Windows reads the EXIF rating, which has no rejected value
+            self.set_or_clear_tag("Exif.Image.Rating", if rating.starts_with('-') { "" } else { rating.as_str() })?;
+        }
+        if let Some(copyright) = &edit.copyright {
+            self.set_or_clear_tag("Exif.Image.Copyright", copyright)?;
+            self.set_or_clear_tag("Xmp.dc.rights", copyright)?;
+        }
+        self.meta.save_to_file(&self.path)
+    }
+    // Shifts the dates other than DateTimeOriginal, like `exiftool -AllDates`, so that they stay consistent with the capture date.
+    fn shift_dates(&self, seconds: i64, offset_time: Option<&str>) -> Result<(), rexiv2::Rexiv2Error> {
+        let shift = Duration::seconds(seconds);
+        for (tag, offset_tag) in [
+            ("Exif.Photo.DateTimeDigitized", "Exif.Photo.OffsetTimeDigitized"),
+            ("Exif.Image.DateTime", "Exif.Photo.OffsetTime"),
+        ] {
+            let Some(date) = self.get_tag_string(tag).and_then(|date| parse_exif_date(&date)?.checked_add(shift)) else {
+                continue;
+            };
+            self.meta.set_tag_string(tag, &format_exif_date(date))?;
+            if let Some(offset_time) = offset_time {
+                self.set_or_clear_tag(offset_tag, offset_time)?;
+            }
+        }
+        for tag in ["Xmp.photoshop.DateCreated", "Xmp.xmp.CreateDate"] {
+            let Some((date, offset)) = self.get_tag_string(tag).and_then(|date| parse_xmp_date(&date)) else {
+                continue;
+            };
+            let Some(date) = date.checked_add(shift) else {
+                continue;
+            };
+            let offset = match offset_time {
+                Some(offset_time) => parse_exif_timezone(offset_time),
+                None => offset,
+            };
+            self.meta.set_tag_string(tag, &format_xmp_date(date, offset))?;
+        }
+        Ok(())
+    }
+    fn set_or_clear_tag(&self, tag: &str, value: &str) -> Result<(), rexiv2::Rexiv2Error> {
+        if value.is_empty() {
+            self.meta.clear_tag(tag);
+            Ok(())
+        } else {
+            self.meta.set_tag_string(tag, value)
+        }
+    }
+    fn set_or_clear_multiple_tag(&self, tag: &str, values: &[&str]) -> Result<(), rexiv2::Rexiv2Error> {
+        self.meta.clear_tag(tag);
+        if values.is_empty() {
+            Ok(())
+        } else {
+            self.meta.set_tag_multiple_strings(tag, values)
+        }
+    }
+
     pub fn regen_uid(&mut self) -> String {
         self.uid = gen_new_uid();
         self.meta
@@ -91,18 +396,47 @@ impl ExifFile {
     }
 
     pub fn to_picture_cache(&self, path: String) -> PictureCache {
+        let location = self.get_location();
+        let (country, region, city) = location
+            .and_then(|(latitude, longitude, _)| reverse_geocode(latitude, longitude))
+            .map_or((None, None, None), |place| (place.country, place.region, place.city));
         PictureCache {
             path,
             uuid_generated: self.uuid_generated,
             date: self.get_date(),
-            location: self.get_location(),
+            capture_date: self.get_capture_date(),
+            location,
+            country,
+            region,
+            city,
             orientation: self.get_orientation(),
             dimensions: self.get_dimensions(),
             camera: self.get_camera(),
+            camera_make: self.get_camera_make(),
             focal_length: self.get_focal_length(),
             exposure_time: self.get_exposure_time(),
             iso_speed: self.get_iso_speed(),
             f_number: self.get_f_number(),
+            lens: self.get_lens(),
+            focal_length_35mm: self.get_focal_length_35mm(),
+            exposure_bias: self.get_exposure_bias(),
+            flash: self.get_flash(),
+            metering_mode: self.get_metering_mode(),
+            white_balance: self.get_white_balance(),
+            software: self.get_software(),
+            offset_time_original: self.get_offset_time_original(),
+            offset_time: self.get_offset_time(),
+            gps_direction: self.get_gps_direction(),
+            file_size: self.get_file_size(),
+            file_format: self.get_file_format(),
+            color: self.get_color(),
+            title: self.get_title(),
+            keywords: self.get_keywords(),
+            hierarchical_keywords: self.get_hierarchical_keywords(),
+            rating: self.get_rating(),
+            label: self.get_label(),
+            description: self.get_description(),
+            copyright: self.get_copyright(),
         }
     }
 }
@@ -126,6 +460,102 @@ impl FromRexiv2<rexiv2::Orientation> for Orientation {
     }
 }
 
+// Removes the metadata of an exported copy of a picture.
+// The location is also removed if the copy was taken in one of the home zones, according to its own metadata.
+pub fn strip_metadata(path: &Path, strip: &MetadataStrip, home_zones: &[HomeZone]) -> Result<(), rexiv2::Rexiv2Error> {
+    let meta = rexiv2::Metadata::new_from_path(path)?;
+    if strip.all {
+        // The orientation is kept, otherwise the copy would be displayed rotated
+        let orientation = meta.get_orientation();
+        meta.clear();
+        if orientation != rexiv2::Orientation::Unspecified {
+            meta.set_orientation(orientation);
+        }
+    } else {
+        let in_home_zone = meta
+            .get_gps_info()
+            .map_or(false, |gps| home_zones.iter().any(|zone| zone.contains(gps.latitude, gps.longitude)));
+        if strip.gps || in_home_zone {
+            meta.delete_gps_info();
+            // Including the structured locations like Xmp.iptcExt.LocationCreated[1]/Iptc4xmpExt:City
+            clear_tags(&meta, |key| key.starts_with("Xmp.exif.GPS") || key.starts_with("Xmp.iptcExt.Location"));
+            // Place names written by the geocoding or by other software
+            for key in LOCATION_NAME_TAGS {
+                meta.clear_tag(key);
+            }
+        }
+        if strip.serials {
+            // Like Exif.Photo.BodySerialNumber, Exif.Canon.SerialNumber, Exif.Nikon3.SerialNO or Xmp.aux.LensSerialNumber
+            clear_tags(&meta, |key| key.to_lowercase().contains("serial"));
+        }
+        if strip.uid {
+            meta.clear_tag("Xmp.PicturesManagerClementGre.uid");
+        }
+    }
+    meta.save_to_file(path)
+}
+const LOCATION_NAME_TAGS: [&str; 10] = [
+    "Xmp.photoshop.City",
+    "Xmp.photoshop.State",
+    "Xmp.photoshop.Country",
+    "Xmp.iptc.Location",
+    "Xmp.iptc.CountryCode",
+    "Iptc.Application2.City",
+    "Iptc.Application2.SubLocation",
+    "Iptc.Application2.ProvinceState",
+    "Iptc.Application2.CountryName",
+    "Iptc.Application2.CountryCode",
+];
+fn clear_tags(meta: &rexiv2::Metadata, filter: impl Fn(&str) -> bool) {
+    for key in [meta.get_exif_tags(), meta.get_xmp_tags()]
+        .into_iter()
+        .flat_map(|keys| keys.unwrap_or_default())
+    {
+        if filter(&key) {
+            meta.clear_tag(&key);
+        }
+    }
+}
+
+// Reads all the EXIF, IPTC and XMP tags of a picture, grouped by namespace in the order of the file.
+// Unlike ExifFile::new, the file is never written, even if it has no uid yet.
+pub fn read_all_tags(path: &Path) -> Option<Vec<MetadataGroup>> {
+    let meta = rexiv2::Metadata::new_from_path(path).ok()?;
+    let keys = [meta.get_exif_tags(), meta.get_iptc_tags(), meta.get_xmp_tags()]
+        .into_iter()
+        .flat_map(|keys| keys.unwrap_or_default());
+
+    let mut groups: Vec<MetadataGroup> = Vec::new();
+    for key in keys {
+        let Some((group_name, tag_name)) = key.rsplit_once('.') else {
+            continue;
+        };
+        // Multiple values (IPTC keywords, XMP bags) are joined as exiv2 returns only the first one
+        let value = match meta.get_tag_multiple_strings(&key) {
+            Ok(values) if values.len() > 1 => values.join("; "),
+            _ => meta.get_tag_string(&key).unwrap_or_default(),
+        };
+        let interpreted = meta.get_tag_interpreted_string(&key).unwrap_or_else(|_| value.clone());
+        let tag = MetadataTag {
+            label: rexiv2::get_tag_label(&key)
+                .ok()
+                .filter(|label| !label.is_empty())
+                .unwrap_or_else(|| tag_name.to_string()),
+            value,
+            interpreted,
+            key: key.clone(),
+        };
+        match groups.iter_mut().find(|group| group.name == group_name) {
+            Some(group) => group.tags.push(tag),
+            None => groups.push(MetadataGroup {
+                name: group_name.to_string(),
+                tags: vec![tag],
+            }),
+        }
+    }
+    Some(groups)
+}
+
 static UID_SEC_COUNT: AtomicU64 = AtomicU64::new(0);
 static UID_LAST_SECS: AtomicU64 = AtomicU64::new(0);
 
@@ -141,3 +571,204 @@ pub fn gen_new_uid() -> String {
 
     format!("{:X}-{:X}", since_the_epoch.as_secs(), UID_SEC_COUNT.fetch_add(1, Ordering::SeqCst))
 }
+
+// Parses an EXIF date, formatted as `YYYY:MM:DD HH:MM:SS`.
+pub fn parse_exif_date(date: &str) -> Option<PrimitiveDateTime> {
+    let (date, time) = date.split_once(' ')?;
+    // Parses the three numbers of the date or the time, checking their number of digits.
+    let parse = |value: &str, digits: [usize; 3]| -> Option<Vec<u32>> {
+        let parts: Vec<&str> = value.split(':').collect();
+        if parts.len() != 3 || parts.iter().zip(digits).any(|(part, digits)| part.len() != digits) {
+            return None;
+        }
+        parts.iter().map(|part| part.parse::<u32>().ok()).collect()
+    };
+    let (date, time) = (parse(date, [4, 2, 2])?, parse(time, [2, 2, 2])?);
+    let date = Date::from_calendar_date(date[0] as i32, Month::try_from(date[1] as u8).ok()?, date[2] as u8).ok()?;
+    let time = Time::from_hms(time[0] as u8, time[1] as u8, time[2] as u8).ok()?;
+    Some(PrimitiveDateTime::new(date, time))
+}
+pub fn format_exif_date(date: PrimitiveDateTime) -> String {
+    format!(
+        "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second()
+    )
+}
+
+// Parses an EXIF timezone, formatted as `±HH:MM` from -12:00 to +14:00, into an offset in seconds.
+pub fn parse_exif_timezone(timezone: &str) -> Option<i32> {
+    let (sign, offset, max_hours) = if let Some(offset) = timezone.strip_prefix('+') {
+        (1, offset, 14)
+    } else {
+        (-1, timezone.strip_prefix('-')?, 12)
+    };
+    let (hours, minutes) = offset.split_once(':')?;
+    let (hours, _) = take_number(hours, 2).filter(|(_, rest)| rest.is_empty())?;
+    let (minutes, _) = take_number(minutes, 2).filter(|(_, rest)| rest.is_empty())?;
+    if minutes >= 60 || hours * 60 + minutes > max_hours * 60 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60) as i32)
+}
+
+// Parses an XMP date (ISO 8601), from `YYYY` to `YYYY-MM-DDThh:mm:ss.s±hh:mm`. Returns the local date and the timezone offset.
+pub fn parse_xmp_date(date: &str) -> Option<(PrimitiveDateTime, Option<i32>)> {
+    let (date, time) = date.split_once('T').unwrap_or((date, ""));
+    let mut parts = date.split('-');
+    let year = parts.next()?.parse::<i32>().ok()?;
+    let month = parts.next().map_or(Some(1), |month| month.parse::<u8>().ok())?;
+    let day = parts.next().map_or(Some(1), |day| day.parse::<u8>().ok())?;
+    let date = Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()?;
+    if time.is_empty() {
+        return Some((date.midnight(), None));
+    }
+
+    let (time, offset) = if let Some(time) = time.strip_suffix('Z') {
+        (time, Some(0))
+    } else if let Some(index) = time.rfind(&['+', '-'][..]) {
+        (&time[..index], Some(parse_exif_timezone(&time[index..])?))
+    } else {
+        (time, None)
+    };
+    let mut parts = time.split(':');
+    let hour = parts.next()?.parse::<u8>().ok()?;
+    let minute = parts.next()?.parse::<u8>().ok()?;
+    // Fractions of seconds are ignored
+    let second = parts.next().map_or(Some(0), |second| second.split('.').next()?.parse::<u8>().ok())?;
+    Some((PrimitiveDateTime::new(date, Time::from_hms(hour, minute, second).ok()?), offset))
+}
+
+// Formats an XMP date (ISO 8601) as `YYYY-MM-DDThh:mm:ss`, followed by the timezone if known.
+pub fn format_xmp_date(date: PrimitiveDateTime, offset: Option<i32>) -> String {
+    let mut formatted = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second()
+    );
+    if let Some(offset) = offset {
+        let sign = if offset < 0 { '-' } else { '+' };
+        formatted.push_str(&format!("{}{:02}:{:02}", sign, offset.abs() / 3600, offset.abs() / 60 % 60));
+    }
+    formatted
+}
+
+// Finds a date in a file name, like `IMG_20230415_103020`, `PXL_20230415_103020123` or `Screenshot 2023-04-15 at 10.30.20`.
+// The time is optional, midnight is used when it is missing.
+pub fn parse_file_name_date(name: &str) -> Option<PrimitiveDateTime> {
+    name.char_indices()
+        .filter(|(i, _)| !name[..*i].ends_with(|c: char| c.is_ascii_digit()))
+        .find_map(|(i, _)| parse_file_name_date_at(&name[i..]))
+}
+fn parse_file_name_date_at(name: &str) -> Option<PrimitiveDateTime> {
+    let (year, name) = take_number(name, 4).filter(|(year, _)| (1900..=2100).contains(year))?;
+    let (separator, name) = take_separator(name, &['-', '_', '.']);
+    let (month, name) = take_number(name, 2)?;
+    let name = take_same_separator(name, separator)?;
+    let (day, name) = take_number(name, 2)?;
+    let date = Date::from_calendar_date(year as i32, Month::try_from(month as u8).ok()?, day as u8).ok()?;
+
+    let name = name.trim_start_matches(&[' ', '_', '-', 'T'][..]);
+    let name = name.strip_prefix("at ").unwrap_or(name);
+    Some(PrimitiveDateTime::new(date, parse_file_name_time(name).unwrap_or(Time::MIDNIGHT)))
+}
+fn parse_file_name_time(name: &str) -> Option<Time> {
+    let (hour, name) = take_number(name, 2)?;
+    let (separator, name) = take_separator(name, &[':', '.', '-', 'h']);
+    let (minute, name) = take_number(name, 2)?;
+    let name = take_same_separator(name, separator)?;
+    let (second, _) = take_number(name, 2)?;
+    Time::from_hms(hour as u8, minute as u8, second as u8).ok()
+}
+
+// Reads a number of exactly `digits` digits at the start of the string.
+fn take_number(value: &str, digits: usize) -> Option<(u32, &str)> {
+    let number = value.get(..digits)?;
+    if !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some((number.parse().ok()?, &value[digits..]))
+}
+fn take_separator<'a>(value: &'a str, separators: &[char]) -> (Option<char>, &'a str) {
+    match value.chars().next() {
+        Some(c) if separators.contains(&c) => (Some(c), &value[c.len_utf8()..]),
+        _ => (None, value),
+    }
+}
+// The separators of a date or a time are all the same, or absent.
+fn take_same_separator(value: &str, separator: Option<char>) -> Option<&str> {
+    match separator {
+        Some(separator) => value.strip_prefix(separator),
+        None => Some(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> PrimitiveDateTime {
+        Date::from_calendar_date(year, Month::try_from(month).unwrap(), day)
+            .unwrap()
+            .with_hms(hour, minute, second)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_file_name_date_finds_common_patterns() {
+        assert_eq!(parse_file_name_date("IMG_20230415_103020"), Some(date(2023, 4, 15, 10, 30, 20)));
+        assert_eq!(parse_file_name_date("PXL_20230415_103020123"), Some(date(2023, 4, 15, 10, 30, 20)));
+        assert_eq!(
+            parse_file_name_date("Screenshot 2023-04-15 at 10.30.20"),
+            Some(date(2023, 4, 15, 10, 30, 20))
+        );
+        assert_eq!(parse_file_name_date("2023-04-15T10:30:20"), Some(date(2023, 4, 15, 10, 30, 20)));
+        // The time is optional
+        assert_eq!(parse_file_name_date("Holidays 2023.04.15"), Some(date(2023, 4, 15, 0, 0, 0)));
+    }
+
+    #[test]
+    fn parse_file_name_date_rejects_invalid_dates() {
+        assert_eq!(parse_file_name_date("IMG_1234"), None);
+        assert_eq!(parse_file_name_date("IMG_20231345_103020"), None);
+        // Separators must be consistent
+        assert_eq!(parse_file_name_date("2023-04_15"), None);
+        // Dates can't start in the middle of a number
+        assert_eq!(parse_file_name_date("12023041500"), None);
+    }
+
+    #[test]
+    fn parse_xmp_date_accepts_partial_dates() {
+        assert_eq!(parse_xmp_date("2023"), Some((date(2023, 1, 1, 0, 0, 0), None)));
+        assert_eq!(parse_xmp_date("2023-04"), Some((date(2023, 4, 1, 0, 0, 0), None)));
+        assert_eq!(parse_xmp_date("2023-04-15T10:30"), Some((date(2023, 4, 15, 10, 30, 0), None)));
+    }
+
+    #[test]
+    fn parse_xmp_date_reads_timezone() {
+        assert_eq!(parse_xmp_date("2023-04-15T10:30:20Z"), Some((date(2023, 4, 15, 10, 30, 20), Some(0))));
+        assert_eq!(
+            parse_xmp_date("2023-04-15T10:30:20.45+02:00"),
+            Some((date(2023, 4, 15, 10, 30, 20), Some(7200)))
+        );
+        assert_eq!(
+            parse_xmp_date("2023-04-15T10:30:20-05:30"),
+            Some((date(2023, 4, 15, 10, 30, 20), Some(-19800)))
+        );
+    }
+
+    #[test]
+    fn parse_xmp_date_rejects_invalid_dates() {
+        assert_eq!(parse_xmp_date("2023-13-01"), None);
+        assert_eq!(parse_xmp_date("2023-04-15T25:00:00"), None);
+        assert_eq!(parse_xmp_date("2023-04-15T10:30:20+15:00"), None);
+    }
+}
@@ -1,3 +1,4 @@
+use std::fs::read_dir;
 use std::path;
 use std::path::Path;
 
@@ -13,3 +14,20 @@ pub fn path_from_unix_path_string(path: String) -> String {
     }
     path
 }
+// True if the name can't be used to access files outside of a directory (no separators, no `..`).
+pub fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+// Total size in bytes of the files of a directory, recursively.
+pub fn get_dir_size(dir: &Path) -> u64 {
+    read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => get_dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
@@ -0,0 +1,154 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use log::{info, warn};
+use tauri::{AppHandle, Wry};
+
+// Offline reverse geocoding, using the GeoNames dumps bundled in the geonames resources directory:
+// a cities file (cities500.txt, cities1000.txt, cities5000.txt or cities15000.txt), admin1CodesASCII.txt and countryInfo.txt.
+// Without the dumps, pictures are simply not geocoded.
+
+const CITIES_FILES: [&str; 4] = ["cities500.txt", "cities1000.txt", "cities5000.txt", "cities15000.txt"];
+// Beyond this distance to the nearest city, only the region and the country are kept.
+const MAX_CITY_DISTANCE_KM: f64 = 30.0;
+// Beyond this distance to the nearest city, the picture is considered taken in the wild (at sea for instance).
+const MAX_REGION_DISTANCE_KM: f64 = 150.0;
+// Cities are indexed in cells of one degree of latitude and longitude, searched up to this number of latitude cells around the picture cell.
+const SEARCH_CELLS_RADIUS: i32 = 2;
+
+static GEONAMES_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+static GEOCODER: OnceLock<Option<Geocoder>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+}
+
+struct City {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    country_code: String,
+    admin1_code: String,
+}
+
+struct Geocoder {
+    cities: Vec<City>,
+    cells: HashMap<(i32, i32), Vec<usize>>, // Indexes of the cities of each cell
+    regions: HashMap<String, String>,       // Name of the admin regions, by "country code.admin1 code"
+    countries: HashMap<String, String>,     // Name of the countries, by ISO code
+}
+
+// Must be called at startup: the dataset is only loaded when the first picture is geocoded.
+pub fn init_geocoding(app: &AppHandle<Wry>) {
+    let _ = GEONAMES_DIR.set(app.path_resolver().resolve_resource("../geonames/"));
+}
+
+// Country, region and city of the nearest known city, None if the dataset is missing or no city is close enough.
+pub fn reverse_geocode(latitude: f64, longitude: f64) -> Option<Place> {
+    GEOCODER
+        .get_or_init(|| Geocoder::load(GEONAMES_DIR.get()?.as_ref()?))
+        .as_ref()?
+        .reverse_geocode(latitude, longitude)
+}
+
+impl Geocoder {
+    fn load(dir: &Path) -> Option<Self> {
+        let start = std::time::Instant::now();
+        let Some(cities_path) = CITIES_FILES.iter().map(|file| dir.join(file)).find(|path| path.is_file()) else {
+            warn!("No GeoNames cities file found in {:?}, pictures locations will not be geocoded.", dir);
+            return None;
+        };
+        let cities = read_tsv(&cities_path, |columns| {
+            Some(City {
+                name: columns.get(1)?.to_string(),
+                latitude: columns.get(4)?.parse().ok()?,
+                longitude: columns.get(5)?.parse().ok()?,
+                country_code: columns.get(8)?.to_string(),
+                admin1_code: columns.get(10)?.to_string(),
+            })
+        })?;
+
+        let mut cells: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (index, city) in cities.iter().enumerate() {
+            cells.entry(get_cell(city.latitude, city.longitude)).or_default().push(index);
+        }
+        // Both files are optional, cities are then geocoded without region or country names
+        let regions = read_tsv(&dir.join("admin1CodesASCII.txt"), |columns| {
+            Some((columns.first()?.to_string(), columns.get(1)?.to_string()))
+        })
+        .map(HashMap::from_iter)
+        .unwrap_or_default();
+        let countries = read_tsv(&dir.join("countryInfo.txt"), |columns| {
+            Some((columns.first()?.to_string(), columns.get(4)?.to_string()))
+        })
+        .map(HashMap::from_iter)
+        .unwrap_or_default();
+
+        info!("🌍 Loaded {} GeoNames cities in {}ms", cities.len(), start.elapsed().as_millis());
+        Some(Self {
+            cities,
+            cells,
+            regions,
+            countries,
+        })
+    }
+
+    fn reverse_geocode(&self, latitude: f64, longitude: f64) -> Option<Place> {
+        let (cell_lat, cell_lon) = get_cell(latitude, longitude);
+        let mut nearest: Option<(&City, f64)> = None;
+        for d_lat in -SEARCH_CELLS_RADIUS..=SEARCH_CELLS_RADIUS {
+            // Cells are narrower near the poles: the longitude window is widened to cover the same distance.
+            let row = cell_lat + d_lat;
+            let pole_side_latitude = (row.abs().max((row + 1).abs()) as f64).min(89.0);
+            let lon_radius = (SEARCH_CELLS_RADIUS as f64 / pole_side_latitude.to_radians().cos()).ceil().min(180.0) as i32;
+            for d_lon in -lon_radius..=lon_radius {
+                // Longitudes wrap around the antimeridian
+                let cell = (cell_lat + d_lat, (cell_lon + d_lon + 180).rem_euclid(360) - 180);
+                for &index in self.cells.get(&cell).into_iter().flatten() {
+                    let city = &self.cities[index];
+                    let distance = get_distance_km(latitude, longitude, city.latitude, city.longitude);
+                    if nearest.map_or(true, |(_, nearest_distance)| distance < nearest_distance) {
+                        nearest = Some((city, distance));
+                    }
+                }
+            }
+        }
+
+        let (city, distance) = nearest.filter(|(_, distance)| *distance <= MAX_REGION_DISTANCE_KM)?;
+        Some(Place {
+            country: self.countries.get(&city.country_code).cloned(),
+            region: self.regions.get(&format!("{}.{}", city.country_code, city.admin1_code)).cloned(),
+            city: (distance <= MAX_CITY_DISTANCE_KM).then(|| city.name.clone()),
+        })
+    }
+}
+
+// Parses the rows of a GeoNames tab separated file, skipping the comments and the invalid rows.
+fn read_tsv<T>(path: &Path, parse_row: impl Fn(&[&str]) -> Option<T>) -> Option<Vec<T>> {
+    let content = fs::read_to_string(path).map_err(|e| warn!("Unable to read {:?}: {}", path, e)).ok()?;
+    Some(
+        content
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| parse_row(&line.split('\t').collect::<Vec<_>>()))
+            .collect(),
+    )
+}
+
+fn get_cell(latitude: f64, longitude: f64) -> (i32, i32) {
+    (latitude.floor() as i32, (longitude.floor() as i32 + 180).rem_euclid(360) - 180)
+}
+
+// Great-circle distance, using the haversine formula.
+fn get_distance_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (d_lat, d_lon) = ((lat2 - lat1).to_radians(), (lon2 - lon1).to_radians());
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    6371.0 * 2.0 * a.sqrt().asin()
+}
@@ -3,4 +3,12 @@ pub mod translator;
 pub mod commands;
 pub mod exif_utils;
 pub mod thumbnails;
-pub mod files_utils;
\ No newline at end of file
+pub mod files_utils;
+pub mod thumbnails_queue;
+pub mod thumbnails_batch;
+pub mod color_management;
+pub mod protocol;
+pub mod previews;
+pub mod tiles;
+pub mod geocoding;
+pub mod tracks;
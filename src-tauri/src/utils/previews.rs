@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+use std::fs::{create_dir_all, metadata, remove_file, write};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+
+use fast_image_resize as fr;
+use image::ColorType;
+use log::{info, warn};
+use tauri::{Manager, State, Window, Wry};
+
+use pm_common::gallery::{GallerySettings, ThumbnailsFormat};
+use pm_common::gallery_cache::Orientation;
+
+use crate::gallery::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+use super::thumbnails::{encode_thumbnail, is_transparent, load_oriented_image, resize_image};
+
+// Previews are display sized renditions of the pictures, used instead of the originals when they are not zoomed in.
+// They are stored in a sub directory of the thumbnails directory, named after the picture uid and their size.
+const PREVIEWS_DIR: &str = "previews";
+// The requested sizes are rounded up to limit the number of cached renditions per picture.
+const PREVIEW_SIZES: [u32; 4] = [512, 1024, 2048, 4096];
+const PREVIEW_QUALITY: u8 = 90;
+
+// Like thumbnails, identical previews are never generated at the same time:
+// a request waits for the running generation of the same preview, then finds it existing.
+#[derive(Default)]
+pub struct PreviewsState {
+    running: Mutex<HashSet<PathBuf>>, // Thumbnails directory, picture uid and size of the previews being generated
+    condvar: Condvar,
+}
+
+impl PreviewsState {
+    pub fn run(&self, job: &PreviewJob) -> Option<(PathBuf, &'static str)> {
+        let key = job.get_path(ThumbnailsFormat::Png).with_extension(""); // Whatever the format
+        {
+            let mut running = self.running.lock().unwrap();
+            while running.contains(&key) {
+                running = self.condvar.wait(running).unwrap();
+            }
+            running.insert(key.clone());
+        }
+        let _running = RunningPreview { state: self, key };
+        job.run()
+    }
+}
+
+// Releases a running preview even if its generation panicked, otherwise the requests for the same preview would wait forever.
+struct RunningPreview<'a> {
+    state: &'a PreviewsState,
+    key: PathBuf,
+}
+
+impl Drop for RunningPreview<'_> {
+    fn drop(&mut self) {
+        self.state.running.lock().unwrap().remove(&self.key);
+        self.state.condvar.notify_all();
+    }
+}
+
+pub struct PreviewJob {
+    img_path: PathBuf,
+    thumbnails_dir: PathBuf,
+    id: String,
+    orientation: Orientation,
+    dimensions: (u32, u32), // Dimensions of the original, without orientation applied
+    size: u32,              // Length of the longest side of the preview
+    settings: GallerySettings,
+}
+
+impl PreviewJob {
+    // Returns None if the picture is unknown. The gallery lock must be released before running the job.
+    pub fn new(gallery: &WindowGallery, id: &str, max: u32) -> Option<Self> {
+        let picture = gallery.gallery.datas_cache.get(id)?;
+        Some(Self {
+            img_path: PathBuf::from(&gallery.path).join(picture.get_path()),
+            thumbnails_dir: gallery.thumbnails_dir.clone(),
+            id: id.to_string(),
+            orientation: picture.orientation,
+            dimensions: picture.dimensions,
+            size: get_preview_size(picture.dimensions, max),
+            settings: gallery.gallery.settings.clone(),
+        })
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    // Returns the path and mimetype of the preview, generating it if it does not exist or is older than the picture.
+    pub fn run(&self) -> Option<(PathBuf, &'static str)> {
+        if let Some(preview) = self.find_existing() {
+            return Some(preview);
+        }
+        self.generate()
+    }
+
+    pub fn find_existing(&self) -> Option<(PathBuf, &'static str)> {
+        let picture_modified = metadata(&self.img_path).and_then(|metadata| metadata.modified()).ok()?;
+        for format in ThumbnailsFormat::ALL {
+            let path = self.get_path(format);
+            let Ok(preview_modified) = metadata(&path).and_then(|metadata| metadata.modified()) else {
+                continue;
+            };
+            if preview_modified >= picture_modified {
+                return Some((path, format.mimetype()));
+            }
+            let _ = remove_file(&path);
+        }
+        None
+    }
+
+    fn generate(&self) -> Option<(PathBuf, &'static str)> {
+        let start = std::time::Instant::now();
+
+        let (width, height) = if self.orientation.swaps_dimensions() {
+            (self.dimensions.1, self.dimensions.0)
+        } else {
+            self.dimensions
+        };
+        let scale = self.size as f64 / width.max(height).max(1) as f64;
+        let dst_width = ((width as f64 * scale).round() as u32).max(1);
+        let dst_height = ((height as f64 * scale).round() as u32).max(1);
+
+        let (img, source) = load_oriented_image(&self.img_path, self.orientation, dst_height)?;
+
+        // Like thumbnails, PNG is only used for pictures that really use transparency.
+        let transparent = is_transparent(&img);
+        let format = match self.settings.thumbnails_format {
+            _ if transparent => ThumbnailsFormat::Png,
+            ThumbnailsFormat::Png => ThumbnailsFormat::Jpeg,
+            format => format,
+        };
+        let (pixels, pixel_type, color_type) = if transparent {
+            (img.to_rgba8().into_raw(), fr::PixelType::U8x4, ColorType::Rgba8)
+        } else {
+            (img.to_rgb8().into_raw(), fr::PixelType::U8x3, ColorType::Rgb8)
+        };
+
+        // The decoded image might be a bit smaller or larger than expected if the cached dimensions are outdated.
+        let buffer = if (img.width(), img.height()) == (dst_width, dst_height) {
+            pixels
+        } else {
+            resize_image(pixels, img.width(), img.height(), pixel_type, dst_width, dst_height, &self.settings)?
+        };
+        let data = encode_thumbnail(&buffer, dst_width, dst_height, color_type, format, PREVIEW_QUALITY)?;
+
+        let path = self.get_path(format);
+        create_dir_all(path.parent()?).ok()?;
+        if let Err(e) = write(&path, data) {
+            warn!("Unable to write preview {:?}, error: {}", path, e);
+            return None;
+        }
+        info!("Generating preview of size {} took {:?} ({:?})", self.size, start.elapsed(), source);
+        Some((path, format.mimetype()))
+    }
+
+    fn get_path(&self, format: ThumbnailsFormat) -> PathBuf {
+        get_previews_dir(&self.thumbnails_dir).join(format!("{}-{}.{}", self.id, self.size, format.extension()))
+    }
+}
+
+// Rounds up the requested size to the next preview size, without upscaling the picture.
+fn get_preview_size(dimensions: (u32, u32), max: u32) -> u32 {
+    let size = PREVIEW_SIZES
+        .iter()
+        .copied()
+        .find(|size| *size >= max)
+        .unwrap_or(PREVIEW_SIZES[PREVIEW_SIZES.len() - 1]);
+    size.min(dimensions.0.max(dimensions.1)).max(1)
+}
+
+pub fn get_previews_dir(thumbnails_dir: &Path) -> PathBuf {
+    thumbnails_dir.join(PREVIEWS_DIR)
+}
+
+// Returns the uid of the picture of a preview file, from its file stem.
+pub fn get_preview_picture_id(file_stem: &str) -> &str {
+    file_stem.rsplit_once('-').map_or(file_stem, |(id, _)| id)
+}
+
+// Generates the preview in background so that loading it through the reqimg protocol is immediate.
+// Returns the size of the preview to request with /get-preview, or None if it could not be generated.
+#[tauri::command]
+pub async fn request_preview(
+    window: Window<Wry>,
+    galleries_state: State<'_, WindowsGalleriesState>,
+    id: String,
+    max: u32,
+) -> Result<Option<u32>, ()> {
+    let job = {
+        let galleries = galleries_state.get_galleries();
+        PreviewJob::new(WindowGallery::get(&galleries, &window), &id, max)
+    };
+    let Some(job) = job else {
+        info!("🖼️ Requested preview of unknown picture {}", id);
+        return Ok(None);
+    };
+    let size = job.size();
+    let app = window.app_handle();
+    let result = tauri::async_runtime::spawn_blocking(move || app.state::<PreviewsState>().run(&job))
+        .await
+        .ok()
+        .flatten();
+    Ok(result.map(|_| size))
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use httpdate::{fmt_http_date, parse_http_date};
+use log::{info, warn};
+use tauri::http::{Request, Response, ResponseBuilder};
+use tauri::{AppHandle, Manager, Wry};
+use url::Url;
+
+use crate::gallery::windows_galleries::WindowsGalleriesState;
+
+use super::files_utils::is_safe_file_name;
+use super::previews::PreviewJob;
+use super::thumbnails::{get_existing_thumbnail, get_image_mimetype};
+use super::tiles::get_existing_tile;
+
+// Tauri 1 protocols can only answer with a whole body: range responses are capped so that
+// media elements requesting open ranges (`bytes=0-`) load big files chunk by chunk.
+const MAX_RANGE_LENGTH: u64 = 8 * 1024 * 1024;
+const MAX_FULL_BODY_LENGTH: u64 = 32 * 1024 * 1024; // Bigger files are sent in parts even without Range header
+
+// Handler of the reqimg protocol, used by the frontend to load thumbnails and images.
+// Every request must define the window label and the picture uid: `reqimg://localhost/get-image?window=<label>&id=<uid>`.
+// Previews also need the length of their longest side: `reqimg://localhost/get-preview?window=<label>&id=<uid>&max=<size>`,
+// and tiles their position in the pyramid: `reqimg://localhost/get-tile?window=<label>&id=<uid>&level=<level>&x=<x>&y=<y>`.
+pub fn handle_reqimg_request(app: &AppHandle<Wry>, request: &Request) -> Result<Response, Box<dyn Error>> {
+    let Ok(url) = Url::parse(request.uri()) else {
+        return bad_request("Invalid url");
+    };
+    let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+    let (Some(label), Some(id)) = (query.get("window"), query.get("id")) else {
+        return bad_request("Missing window or id parameter");
+    };
+    // The uid is used to build file names: it must not contain path separators.
+    if !is_safe_file_name(id) {
+        return bad_request("Invalid id parameter");
+    }
+
+    // The galleries lock is only held while resolving the requested file, not while reading it.
+    let (gallery_path, thumbnails_dir, picture_path, preview_job) = {
+        let galleries_state = app.state::<WindowsGalleriesState>();
+        let galleries = galleries_state.get_galleries();
+        let Some(gallery) = galleries.iter().find(|gallery| gallery.window_label == *label) else {
+            return not_found(&format!("Unknown window {}", label));
+        };
+        let Some(picture) = gallery.gallery.datas_cache.get(id) else {
+            return not_found(&format!("Unknown picture {}", id));
+        };
+        let preview_job = query
+            .get("max")
+            .and_then(|max| max.parse::<u32>().ok())
+            .and_then(|max| PreviewJob::new(gallery, id, max));
+        (
+            PathBuf::from(&gallery.path),
+            gallery.thumbnails_dir.clone(),
+            picture.get_path(),
+            preview_job,
+        )
+    };
+
+    match url.path() {
+        "/get-thumbnail" => {
+            // The frontend must make sure the thumbnail exists before by calling the command request_thumbnail.
+            if let Some((path, mimetype)) = get_existing_thumbnail(&thumbnails_dir, id) {
+                serve_file(request, &path, mimetype)
+            } else {
+                not_found(&format!("Can't read thumbnail {}", id))
+            }
+        }
+        "/get-image" => {
+            let path = gallery_path.join(picture_path);
+            if !is_inside(&path, &gallery_path) {
+                warn!("🖼️ Refusing to serve image {:?} located outside of the gallery", path);
+                return not_found(&format!("Can't read image {}", id));
+            }
+            serve_file(request, &path, get_image_mimetype(&path))
+        }
+        "/get-preview" => {
+            // The frontend must make sure the preview exists before by calling the command request_preview,
+            // as generating it here would block the webview.
+            let Some(job) = preview_job else {
+                return bad_request("Missing or invalid max parameter");
+            };
+            if let Some((path, mimetype)) = job.find_existing() {
+                serve_file(request, &path, mimetype)
+            } else {
+                not_found(&format!("Can't read preview {}", id))
+            }
+        }
+        "/get-tile" => {
+            // The frontend must make sure the pyramid exists before by calling the command request_tiles.
+            let coordinates = ["level", "x", "y"].map(|key| query.get(key).and_then(|value| value.parse::<u32>().ok()));
+            let [Some(level), Some(x), Some(y)] = coordinates else {
+                return bad_request("Missing or invalid level, x or y parameter");
+            };
+            if let Some((path, mimetype)) = get_existing_tile(&thumbnails_dir, id, level, x, y) {
+                serve_file(request, &path, mimetype)
+            } else {
+                not_found(&format!("Can't read tile {} {}_{} of picture {}", level, x, y, id))
+            }
+        }
+        path => not_found(&format!("Unknown path {}", path)),
+    }
+}
+
+// Answers with the file content, supporting conditional and single range requests.
+// The ETag is derived from the size and the modification date, which change whenever the file is rewritten.
+// Tauri 1 protocols can't stream a body: whole files are read at once in a buffer of their exact size,
+// up to MAX_FULL_BODY_LENGTH. Bigger files are always sent in parts of at most MAX_RANGE_LENGTH, that the client has to request one by one.
+fn serve_file(request: &Request, path: &Path, mimetype: &str) -> Result<Response, Box<dyn Error>> {
+    let Ok(mut file) = File::open(path) else {
+        return not_found(&format!("Can't read file {:?}", path));
+    };
+    let metadata = file.metadata()?;
+    let size = metadata.len();
+    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+    let etag = format!("\"{:x}-{:x}\"", size, modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+
+    let builder = ResponseBuilder::new()
+        .header("ETag", &etag)
+        .header("Last-Modified", fmt_http_date(modified))
+        .header("Cache-Control", "no-cache") // Files can be edited, the webview must always revalidate
+        .header("Accept-Ranges", "bytes");
+
+    if is_not_modified(request, &etag, modified) {
+        return builder.status(304).body(Vec::new());
+    }
+
+    // Multiple ranges are not supported, they are ignored as allowed by RFC 9110.
+    let range = get_header(request, "Range")
+        .filter(|range| !range.contains(','))
+        .filter(|_| is_range_applicable(request, &etag, modified));
+    let bounds = match range {
+        Some(range) => match parse_range(range, size) {
+            Some(bounds) => Some(bounds),
+            None => return builder.status(416).header("Content-Range", format!("bytes */{}", size)).body(Vec::new()),
+        },
+        // Too big to be buffered at once: only the first part is sent, as for an open ended range
+        None if size > MAX_FULL_BODY_LENGTH => Some((0, size - 1)),
+        None => None,
+    };
+    if let Some((start, end)) = bounds {
+        let end = end.min(start + MAX_RANGE_LENGTH - 1);
+        let mut data = vec![0; (end - start + 1) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut data)?;
+        return builder
+            .status(206)
+            .mimetype(mimetype)
+            .header("Content-Range", format!("bytes {}-{}/{}", start, end, size))
+            .body(data);
+    }
+
+    let mut data = vec![0; size as usize];
+    file.read_exact(&mut data)?;
+    builder.mimetype(mimetype).body(data)
+}
+
+// If-None-Match takes precedence over If-Modified-Since (RFC 9110 section 13.1.3).
+fn is_not_modified(request: &Request, etag: &str, modified: SystemTime) -> bool {
+    if let Some(if_none_match) = get_header(request, "If-None-Match") {
+        return if_none_match
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == etag || tag == "*");
+    }
+    if let Some(since) = get_header(request, "If-Modified-Since").and_then(|since| parse_http_date(since).ok()) {
+        return as_secs(modified) <= as_secs(since);
+    }
+    false
+}
+
+// With If-Range, the range only applies if the file did not change since the client got its first part,
+// otherwise the whole file is sent (RFC 9110 section 13.1.5). Entity tags are compared strongly.
+fn is_range_applicable(request: &Request, etag: &str, modified: SystemTime) -> bool {
+    let Some(if_range) = get_header(request, "If-Range").map(str::trim) else {
+        return true;
+    };
+    if if_range.starts_with('"') || if_range.starts_with("W/") {
+        return if_range == etag;
+    }
+    parse_http_date(if_range).map_or(false, |date| as_secs(date) == as_secs(modified))
+}
+
+// HTTP dates have a one second precision
+fn as_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+// Parses a single `bytes=<start>-<end>` range, `bytes=<start>-` or `bytes=-<suffix length>`.
+// Returns the inclusive bounds, or None if the range is not satisfiable.
+fn parse_range(range: &str, size: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.trim().strip_prefix("bytes=")?.split_once('-')?;
+    if size == 0 {
+        return None;
+    }
+    let (start, end) = if start.is_empty() {
+        let suffix: u64 = end.trim().parse().ok()?;
+        if suffix == 0 {
+            return None;
+        }
+        (size.saturating_sub(suffix), size - 1)
+    } else {
+        let start: u64 = start.trim().parse().ok()?;
+        let end = if end.trim().is_empty() {
+            size - 1
+        } else {
+            end.trim().parse::<u64>().ok()?.min(size - 1)
+        };
+        (start, end)
+    };
+    if start > end {
+        return None;
+    }
+    Some((start, end))
+}
+
+fn get_header<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+// Resolves symbolic links and `..` components before comparing paths.
+fn is_inside(path: &Path, dir: &Path) -> bool {
+    match (path.canonicalize(), dir.canonicalize()) {
+        (Ok(path), Ok(dir)) => path.starts_with(dir),
+        _ => false,
+    }
+}
+
+fn bad_request(message: &str) -> Result<Response, Box<dyn Error>> {
+    info!("🖼️ Bad reqimg request: {}", message);
+    ResponseBuilder::new().status(400).body(Vec::new())
+}
+fn not_found(message: &str) -> Result<Response, Box<dyn Error>> {
+    info!("🖼️ {}", message);
+    ResponseBuilder::new().status(404).body(Vec::new())
+}
@@ -1,19 +1,33 @@
-use std::fs::{create_dir_all, read, write};
-use std::io::BufWriter;
+use std::fs::{copy, create_dir_all, metadata, read_dir, remove_dir, remove_dir_all, remove_file, rename, write};
+use std::io::{BufWriter, Cursor};
 use std::num::NonZeroU32;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
 use std::{ffi::OsStr, path::PathBuf};
 
 use fast_image_resize as fr;
-use image::codecs::png::PngEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::{WebPEncoder, WebPQuality};
 use image::io::Reader as ImageReader;
-use image::{ColorType, ImageEncoder};
+use image::{ColorType, DynamicImage, ImageEncoder, RgbImage, RgbaImage};
 use log::{info, warn};
-use tauri::{Window, Wry};
+use tauri::{AppHandle, Window, Wry};
 
+use pm_common::gallery::{GallerySettings, ThumbnailsFilter, ThumbnailsFormat, ThumbnailsLocation};
 use pm_common::gallery_cache::Orientation;
 
+use crate::gallery::gallery_cache::{ThumbnailCache, ThumbnailFingerprint, ThumbnailSource};
+use crate::gallery::gallery_data::Gallery;
 use crate::gallery::windows_galleries::{WindowGallery, WindowsGalleriesState};
 
+use super::color_management::{convert_to_srgb, decode_with_icc, embed_srgb_in_jpeg, embed_srgb_in_webp, read_icc_profile};
+use super::files_utils::get_dir_size;
+use super::previews::{get_preview_picture_id, get_previews_dir};
+use super::tiles::get_tiles_dir;
+
+const SHARPENING_SIGMA: f32 = 0.5;
+const SHARPENING_THRESHOLD: i32 = 2;
+
 // First called function to determine image dimension
 // Dimensions are in the right orientation
 #[tauri::command]
@@ -23,47 +37,135 @@ pub fn get_image_dimensions(window: Window<Wry>, galleries_state: tauri::State<'
     let cache = gallery.gallery.datas_cache.get(&id)?;
     let (w, h) = cache.dimensions;
 
-    Some(match cache.orientation {
-        Orientation::Rotate90 | Orientation::Rotate270 | Orientation::Rotate90HorizontalFlip | Orientation::Rotate90VerticalFlip => (h, w),
-        _ => (w, h),
-    })
+    Some(if cache.orientation.swaps_dimensions() { (h, w) } else { (w, h) })
 }
 
-// Second called function to make sure thumbnail exists
-#[tauri::command]
-pub async fn gen_image_thumbnail(window: Window<Wry>, galleries_state: tauri::State<'_, WindowsGalleriesState>, id: String) -> Result<bool, ()> {
-    let path;
-    let gallery_path;
-    let orientation;
-    {
-        let galleries = galleries_state.get_galleries();
-        let gallery = WindowGallery::get(&galleries, &window);
-        orientation = gallery.gallery.datas_cache.get(&id).unwrap().orientation;
-        path = gallery.gallery.datas_cache.get(&id).unwrap().get_path();
-        gallery_path = gallery.path.clone();
-    }
-    Ok(gen_thumbnail(gallery_path, path, id, orientation, 280).await.is_some())
-}
-async fn gen_thumbnail(gallery_path: String, image_path: String, id: String, orientation: Orientation, target_height: u32) -> Option<()> {
-    // Check if thumbnail already exists
-    let thumb_path = PathBuf::from(&gallery_path).join(".thumbnails").join(format!("{}.png", &id));
-    if thumb_path.exists() {
-        return Some(());
+// Second called function (through the thumbnails queue) to make sure thumbnail exists
+// Returns the cache of the existing thumbnail if it is up-to-date and in the right format, migrating old PNG thumbnails if needed.
+// The returned cache must be recorded if it differs from the given one.
+// Thumbnails without cache have been generated before fingerprints were recorded, and are considered up-to-date.
+pub fn check_existing_thumbnail(
+    thumbnails_dir: &Path,
+    id: &str,
+    settings: &GallerySettings,
+    cache: Option<&ThumbnailCache>,
+    fingerprint: &ThumbnailFingerprint,
+) -> Option<ThumbnailCache> {
+    if cache.map_or(false, |cache| cache.fingerprint != *fingerprint) {
+        info!("🖼️ Picture {} changed since its thumbnail was generated", id);
+        remove_thumbnails(thumbnails_dir, id);
+        return None;
     }
-    let start = std::time::Instant::now();
-
-    let img_path: PathBuf = PathBuf::from(&gallery_path).join(&image_path);
-    let img = ImageReader::open(img_path.clone());
-    if let Err(e) = img {
-        warn!("Unable to open image: {:?}, error: {}", img_path, e);
+    if cache.and_then(|cache| cache.is_rendered_with(settings)) == Some(false) {
+        info!("🖼️ Rendering settings changed since the thumbnail of picture {} was generated", id);
+        remove_thumbnails(thumbnails_dir, id);
         return None;
     }
-    let img = img.ok()?.decode();
-    if let Err(e) = img {
-        warn!("Unable to decode image: {:?}, error: {}", img_path, e);
+    let (existing_path, existing_format) = find_thumbnail(thumbnails_dir, id)?;
+    let mut cache = cache.cloned().unwrap_or_else(|| ThumbnailCache::unknown(*fingerprint));
+    if existing_format == settings.thumbnails_format || (existing_format == ThumbnailsFormat::Png && cache.transparent) {
+        return Some(cache);
+    }
+    // Old thumbnails are all PNG: converting them is much cheaper than decoding the original again.
+    if existing_format == ThumbnailsFormat::Png {
+        if let Some(transparent) = migrate_png_thumbnail(&existing_path, settings) {
+            cache.transparent = transparent;
+            return Some(cache);
+        }
+    }
+    let _ = remove_file(&existing_path);
+    None
+}
+
+// Cheap version of check_existing_thumbnail, used before queuing a thumbnail: nothing is decoded nor removed.
+// Outdated and old PNG thumbnails are left to check_existing_thumbnail, called by the thumbnails workers.
+pub fn find_up_to_date_thumbnail(
+    thumbnails_dir: &Path,
+    id: &str,
+    settings: &GallerySettings,
+    cache: Option<&ThumbnailCache>,
+    fingerprint: &ThumbnailFingerprint,
+) -> Option<ThumbnailCache> {
+    if cache.map_or(false, |cache| {
+        cache.fingerprint != *fingerprint || cache.is_rendered_with(settings) == Some(false)
+    }) {
         return None;
     }
-    let img = img.ok()?;
+    let (_, existing_format) = find_thumbnail(thumbnails_dir, id)?;
+    let cache = cache.cloned().unwrap_or_else(|| ThumbnailCache::unknown(*fingerprint));
+    (existing_format == settings.thumbnails_format || (existing_format == ThumbnailsFormat::Png && cache.transparent)).then_some(cache)
+}
+
+// Fingerprint of the picture file, None if it can't be read.
+pub fn get_thumbnail_fingerprint(img_path: &Path, orientation: Orientation) -> Option<ThumbnailFingerprint> {
+    let metadata = metadata(img_path).ok()?;
+    Some(ThumbnailFingerprint {
+        modified: metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs(),
+        size: metadata.len(),
+        orientation,
+    })
+}
+
+pub struct GeneratedThumbnail {
+    pub source: ThumbnailSource,
+    pub color: (u8, u8, u8), // Dominant color of the thumbnail
+    pub transparent: bool,   // Encoded in PNG whatever the thumbnails format
+}
+
+pub fn gen_thumbnail(
+    gallery_path: &str,
+    image_path: &str,
+    thumbnails_dir: &Path,
+    id: &str,
+    orientation: Orientation,
+    target_height: u32,
+    settings: &GallerySettings,
+) -> Option<GeneratedThumbnail> {
+    let start = std::time::Instant::now();
+
+    let img_path: PathBuf = PathBuf::from(gallery_path).join(image_path);
+    let (img, source) = load_oriented_image(&img_path, orientation, target_height)?;
+
+    // PNG is only kept for pictures that really use transparency.
+    let transparent = is_transparent(&img);
+    let format = if transparent {
+        ThumbnailsFormat::Png
+    } else {
+        settings.thumbnails_format
+    };
+    let (pixels, pixel_type, color_type, channels) = if transparent {
+        (img.to_rgba8().into_raw(), fr::PixelType::U8x4, ColorType::Rgba8, 4)
+    } else {
+        (img.to_rgb8().into_raw(), fr::PixelType::U8x3, ColorType::Rgb8, 3)
+    };
+
+    let dst_width = target_height * img.width() / img.height();
+    let buffer = resize_image(pixels, img.width(), img.height(), pixel_type, dst_width, target_height, settings)?;
+
+    // Write destination image in the thumbnails format
+    let data = encode_thumbnail(&buffer, dst_width, target_height, color_type, format, settings.thumbnails_quality)?;
+
+    let thumb_path = get_thumbnail_path(thumbnails_dir, id, format);
+    create_dir_all(thumb_path.parent()?).expect("Unable to create gallery directory.");
+    write(thumb_path, data).ok()?;
+
+    info!("Generating thumbnail took {:?} ({:?})", start.elapsed(), source);
+
+    Some(GeneratedThumbnail {
+        source,
+        color: get_dominant_color(&buffer, channels)?,
+        transparent,
+    })
+}
+
+// Loads the picture in sRGB with the orientation applied, from an embedded preview at least as high as the target height if possible.
+pub fn load_oriented_image(img_path: &Path, orientation: Orientation, target_height: u32) -> Option<(DynamicImage, ThumbnailSource)> {
+    let ((img, icc), source) = if let Some(preview) = load_embedded_preview(img_path, orientation, target_height) {
+        (preview, ThumbnailSource::EmbeddedPreview)
+    } else {
+        (decode_image(img_path)?, ThumbnailSource::FullDecode)
+    };
+    let img = convert_to_srgb(img, icc.as_deref());
 
     // Rotate image if needed
     let img = match orientation {
@@ -76,50 +178,374 @@ async fn gen_thumbnail(gallery_path: String, image_path: String, id: String, ori
         Orientation::VerticalFlip => img.flipv(),
         _ => img,
     };
+    Some((img, source))
+}
+
+// Dominant color of RGB or RGBA pixels, ignoring the alpha channel: the average of the most common colors, quantized to 4 bits per channel.
+// Unlike the plain average, a blue sky above a dark forest gives blue or green rather than a muddy mix of both.
+pub fn get_dominant_color(pixels: &[u8], channels: usize) -> Option<(u8, u8, u8)> {
+    let mut buckets = vec![(0u64, [0u64; 3]); 4096]; // Count and sum of the pixels of each quantized color
+    for pixel in pixels.chunks_exact(channels) {
+        let bucket = &mut buckets[((pixel[0] as usize >> 4) << 8) | ((pixel[1] as usize >> 4) << 4) | (pixel[2] as usize >> 4)];
+        bucket.0 += 1;
+        for (sum, value) in bucket.1.iter_mut().zip(pixel) {
+            *sum += *value as u64;
+        }
+    }
+    let (count, sum) = buckets.into_iter().max_by_key(|(count, _)| *count).filter(|(count, _)| *count > 0)?;
+    Some(((sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8))
+}
 
-    let width = NonZeroU32::new(img.width())?;
-    let height = NonZeroU32::new(img.height())?;
-    let src_image = fr::Image::from_vec_u8(width, height, img.to_rgba8().into_raw(), fr::PixelType::U8x4).ok()?;
+// Resizes raw pixels (RGB8 or RGBA8) with the filter defined in the settings, then sharpens them if enabled.
+pub fn resize_image(
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    pixel_type: fr::PixelType,
+    dst_width: u32,
+    dst_height: u32,
+    settings: &GallerySettings,
+) -> Option<Vec<u8>> {
+    let src_image = fr::Image::from_vec_u8(NonZeroU32::new(width)?, NonZeroU32::new(height)?, pixels, pixel_type).ok()?;
 
     // Create container for data of destination image
-    let dst_width = NonZeroU32::new(target_height * img.width() / img.height())?;
-    let dst_height = NonZeroU32::new(target_height)?;
-    let mut dst_image = fr::Image::new(dst_width, dst_height, src_image.pixel_type());
+    let mut dst_image = fr::Image::new(NonZeroU32::new(dst_width)?, NonZeroU32::new(dst_height)?, pixel_type);
 
     // Get mutable view of destination image data
     let mut dst_view = dst_image.view_mut();
 
     // Resize source image
-    let mut resizer = fr::Resizer::new(fr::ResizeAlg::Convolution(fr::FilterType::Box));
+    let filter = match settings.thumbnails_filter {
+        ThumbnailsFilter::Box => fr::FilterType::Box,
+        ThumbnailsFilter::Bilinear => fr::FilterType::Bilinear,
+        ThumbnailsFilter::CatmullRom => fr::FilterType::CatmullRom,
+        ThumbnailsFilter::Lanczos3 => fr::FilterType::Lanczos3,
+    };
+    let mut resizer = fr::Resizer::new(fr::ResizeAlg::Convolution(filter));
     resizer.resize(&src_image.view(), &mut dst_view).ok()?;
 
-    // Write destination image as PNG-file
+    let buffer = dst_image.into_vec();
+    if !settings.thumbnails_sharpening {
+        return Some(buffer);
+    }
+    // Light unsharp mask, restoring the fine details softened by the downscaling
+    let img = if pixel_type == fr::PixelType::U8x4 {
+        DynamicImage::ImageRgba8(RgbaImage::from_raw(dst_width, dst_height, buffer)?)
+    } else {
+        DynamicImage::ImageRgb8(RgbImage::from_raw(dst_width, dst_height, buffer)?)
+    };
+    Some(img.unsharpen(SHARPENING_SIGMA, SHARPENING_THRESHOLD).into_bytes())
+}
+
+// Returns the decoded image with its ICC profile
+pub fn decode_image(img_path: &Path) -> Option<(DynamicImage, Option<Vec<u8>>)> {
+    let img = ImageReader::open(img_path).and_then(|reader| reader.with_guessed_format());
+    if let Err(e) = img {
+        warn!("Unable to open image: {:?}, error: {}", img_path, e);
+        return None;
+    }
+    let img = decode_with_icc(img.ok()?);
+    if let Err(e) = img {
+        warn!("Unable to decode image: {:?}, error: {}", img_path, e);
+        return None;
+    }
+    img.ok()
+}
+
+// Decoding the smallest embedded preview that is large enough is much faster than decoding the original.
+// Previews are stored without orientation applied, like the original pixels.
+// Previews rarely have their own ICC profile: they then share the color space of the original.
+fn load_embedded_preview(img_path: &Path, orientation: Orientation, target_height: u32) -> Option<(DynamicImage, Option<Vec<u8>>)> {
+    let meta = rexiv2::Metadata::new_from_path(img_path).ok()?;
+    let (img_width, img_height) = (meta.get_pixel_width() as u32, meta.get_pixel_height() as u32);
+    if img_width == 0 || img_height == 0 {
+        return None;
+    }
+
+    let preview = meta
+        .get_preview_images()?
+        .into_iter()
+        .filter(|preview| {
+            let (width, height) = (preview.get_width(), preview.get_height());
+            let oriented_height = if orientation.swaps_dimensions() { width } else { height };
+            // Some cameras add black bars to the preview to fit a fixed aspect ratio.
+            let same_ratio = (width as f64 / height.max(1) as f64 - img_width as f64 / img_height as f64).abs() < 0.02;
+            oriented_height >= target_height && same_ratio
+        })
+        .min_by_key(|preview| preview.get_width() * preview.get_height())?;
+
+    let data = preview.get_data().ok()?;
+    let (img, icc) = decode_with_icc(ImageReader::new(Cursor::new(data)).with_guessed_format().ok()?).ok()?;
+    Some((img, icc.or_else(|| read_icc_profile(img_path))))
+}
+
+// Re-encodes a PNG thumbnail in the format defined in the settings, unless it has transparency.
+// Returns whether the thumbnail has been kept in PNG because of its transparency.
+fn migrate_png_thumbnail(png_path: &Path, settings: &GallerySettings) -> Option<bool> {
+    let img = ImageReader::open(png_path).ok()?.decode().ok()?;
+    if is_transparent(&img) {
+        return Some(true);
+    }
+    let format = settings.thumbnails_format;
+    let data = encode_thumbnail(
+        &img.to_rgb8().into_raw(),
+        img.width(),
+        img.height(),
+        ColorType::Rgb8,
+        format,
+        settings.thumbnails_quality,
+    )?;
+
+    write(png_path.with_extension(format.extension()), data).ok()?;
+    let _ = remove_file(png_path);
+    Some(false)
+}
+
+pub fn encode_thumbnail(buffer: &[u8], width: u32, height: u32, color_type: ColorType, format: ThumbnailsFormat, quality: u8) -> Option<Vec<u8>> {
+    let quality = quality.clamp(1, 100);
     let mut result_buf = BufWriter::new(Vec::new());
-    PngEncoder::new(&mut result_buf)
-        .write_image(dst_image.buffer(), dst_width.get(), dst_height.get(), ColorType::Rgba8)
-        .ok()?;
+    match format {
+        ThumbnailsFormat::Jpeg => JpegEncoder::new_with_quality(&mut result_buf, quality).write_image(buffer, width, height, color_type),
+        ThumbnailsFormat::WebP => {
+            WebPEncoder::new_with_quality(&mut result_buf, WebPQuality::lossy(quality)).write_image(buffer, width, height, color_type)
+        }
+        ThumbnailsFormat::Png => return encode_png(buffer, width, height, color_type),
+    }
+    .ok()?;
+    let data = result_buf.into_inner().ok()?;
 
-    create_dir_all(thumb_path.parent()?).expect("Unable to create gallery directory.");
-    write(thumb_path, result_buf.into_inner().ok()?).ok()?;
+    // Pixels have been converted to sRGB, the profile is embedded so that viewers don't have to guess it.
+    Some(match format {
+        ThumbnailsFormat::Jpeg => embed_srgb_in_jpeg(data),
+        ThumbnailsFormat::WebP => embed_srgb_in_webp(data, width, height),
+        ThumbnailsFormat::Png => data,
+    })
+}
 
-    info!("Generating thumbnail took {:?}", start.elapsed());
+// PNG files are tagged with a sRGB chunk instead of a full ICC profile.
+fn encode_png(buffer: &[u8], width: u32, height: u32, color_type: ColorType) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(if color_type == ColorType::Rgba8 {
+        png::ColorType::Rgba
+    } else {
+        png::ColorType::Rgb
+    });
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_srgb(png::SrgbRenderingIntent::Perceptual);
+    encoder.write_header().ok()?.write_image_data(buffer).ok()?;
+    Some(data)
+}
 
-    Some(())
+// The alpha channel is checked in place, without converting the image.
+pub fn is_transparent(img: &DynamicImage) -> bool {
+    match img {
+        DynamicImage::ImageLumaA8(img) => img.pixels().any(|p| p.0[1] != u8::MAX),
+        DynamicImage::ImageRgba8(img) => img.pixels().any(|p| p.0[3] != u8::MAX),
+        DynamicImage::ImageLumaA16(img) => img.pixels().any(|p| p.0[1] != u16::MAX),
+        DynamicImage::ImageRgba16(img) => img.pixels().any(|p| p.0[3] != u16::MAX),
+        DynamicImage::ImageRgba32F(img) => img.pixels().any(|p| p.0[3] < 1.0),
+        _ => false,
+    }
 }
 
-// Third called function to get thumbnail data through custom protocol
-pub fn get_existing_thumbnail(gallery_path: &str, id: &str) -> Option<Vec<u8>> {
-    let thumb_path = PathBuf::from(&gallery_path).join(".thumbnails").join(format!("{}.png", &id));
-    if let Ok(data) = read(thumb_path.clone()) {
-        return Some(data);
+// Thumbnails are stored in the gallery, or in the app cache directory under the gallery uid.
+pub fn get_thumbnails_dir(app: &AppHandle<Wry>, gallery_path: &str, gallery: &Gallery) -> PathBuf {
+    match gallery.settings.thumbnails_location {
+        ThumbnailsLocation::Gallery => PathBuf::from(gallery_path).join(".thumbnails"),
+        ThumbnailsLocation::AppCache => get_app_cache_thumbnails_dir(app).join(&gallery.uid),
     }
-    None
+}
+fn get_app_cache_thumbnails_dir(app: &AppHandle<Wry>) -> PathBuf {
+    app.path_resolver()
+        .app_cache_dir()
+        .expect("Unable to get app cache directory")
+        .join("thumbnails")
+}
+
+// Moves the thumbnails when their location changes. Thumbnails that can't be moved will be generated again.
+pub fn move_thumbnails(from: &Path, to: &Path) {
+    if from == to {
+        return;
+    }
+    let Ok(entries) = read_dir(from) else {
+        return;
+    };
+    move_thumbnails(&get_previews_dir(from), &get_previews_dir(to));
+    // Tile pyramids are big and nested, they are built again when needed.
+    let _ = remove_dir_all(get_tiles_dir(from));
+    if let Err(e) = create_dir_all(to) {
+        warn!("Unable to create thumbnails directory {:?}, error: {}", to, e);
+        return;
+    }
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        // Renaming is not possible across file systems
+        let dest = to.join(entry.file_name());
+        if rename(&path, &dest).is_err() {
+            if copy(&path, &dest).is_err() {
+                continue;
+            }
+            let _ = remove_file(&path);
+        }
+        count += 1;
+    }
+    let _ = remove_dir(from); // Only succeeds if all thumbnails have been moved
+    info!("🖼️ Moved {} thumbnails from {:?} to {:?}", count, from, to);
+}
+
+// Deletes the oldest thumbnails of the app cache directory until its size is under the limit (in MB, 0 for unlimited).
+// Thumbnails stored in galleries are never deleted.
+pub fn enforce_thumbnails_cache_limit(app: &AppHandle<Wry>, limit: u32) {
+    if limit == 0 {
+        return;
+    }
+    let limit = limit as u64 * 1_000_000;
+
+    // Tile pyramids are deleted as a whole.
+    let mut entries = Vec::new();
+    for gallery_dir in read_dir(get_app_cache_thumbnails_dir(app)).into_iter().flatten().flatten() {
+        let gallery_dir = gallery_dir.path();
+        for dir in [gallery_dir.clone(), get_previews_dir(&gallery_dir), get_tiles_dir(&gallery_dir)] {
+            for entry in read_dir(dir).into_iter().flatten().flatten() {
+                if let Ok(metadata) = entry.metadata() {
+                    let path = entry.path();
+                    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+                    if metadata.is_file() {
+                        entries.push((modified, metadata.len(), path));
+                    } else if path.parent() == Some(&get_tiles_dir(&gallery_dir)) {
+                        entries.push((modified, get_dir_size(&path), path));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut size: u64 = entries.iter().map(|(_, entry_size, _)| entry_size).sum();
+    if size <= limit {
+        return;
+    }
+    entries.sort_by_key(|(modified, _, _)| *modified);
+    let mut count = 0;
+    for (_, entry_size, path) in entries {
+        if size <= limit {
+            break;
+        }
+        let removed = if path.is_dir() { remove_dir_all(&path) } else { remove_file(&path) };
+        if removed.is_ok() {
+            size -= entry_size;
+            count += 1;
+        }
+    }
+    info!("🖼️ Deleted {} thumbnails to respect the cache size limit", count);
+}
+
+fn get_thumbnail_path(thumbnails_dir: &Path, id: &str, format: ThumbnailsFormat) -> PathBuf {
+    thumbnails_dir.join(format!("{}.{}", id, format.extension()))
+}
+// A thumbnail might exist in any format if the settings changed since it was generated.
+fn find_thumbnail(thumbnails_dir: &Path, id: &str) -> Option<(PathBuf, ThumbnailsFormat)> {
+    ThumbnailsFormat::ALL
+        .iter()
+        .map(|format| (get_thumbnail_path(thumbnails_dir, id, *format), *format))
+        .find(|(path, _)| path.exists())
+}
+
+// Removes the thumbnails not generated with the current rendering settings so that they are generated again.
+// Returns the number of removed thumbnails.
+pub fn remove_outdated_thumbnails(gallery: &mut WindowGallery) -> usize {
+    let settings = &gallery.gallery.settings;
+    let thumbnails_cache = &mut gallery.gallery.thumbnails_cache;
+    let mut count = 0;
+    for id in gallery.gallery.datas_cache.keys() {
+        let cache = thumbnails_cache.get(id);
+        if cache.and_then(|cache| cache.is_rendered_with(settings)) != Some(true) && find_thumbnail(&gallery.thumbnails_dir, id).is_some() {
+            remove_thumbnails(&gallery.thumbnails_dir, id);
+            thumbnails_cache.remove(id);
+            count += 1;
+        }
+    }
+    info!("🖼️ Removed {} outdated thumbnails", count);
+    count
+}
+
+fn remove_thumbnails(thumbnails_dir: &Path, id: &str) {
+    for format in ThumbnailsFormat::ALL {
+        let _ = remove_file(get_thumbnail_path(thumbnails_dir, id, format));
+    }
+}
+
+// Third called function to get thumbnail path and mimetype through custom protocol
+pub fn get_existing_thumbnail(thumbnails_dir: &Path, id: &str) -> Option<(PathBuf, &'static str)> {
+    let (thumb_path, format) = find_thumbnail(thumbnails_dir, id)?;
+    Some((thumb_path, format.mimetype()))
+}
+
+// Deletes the thumbnails, previews and tile pyramids of pictures that are no longer in the gallery.
+// Returns the number of deleted thumbnails and the reclaimed space in bytes.
+#[tauri::command]
+pub fn clean_thumbnails(window: Window<Wry>, galleries_state: tauri::State<'_, WindowsGalleriesState>) -> (usize, u64) {
+    let mut galleries = galleries_state.get_galleries();
+    let gallery = WindowGallery::get_mut(&mut galleries, &window);
+
+    let datas_cache = &gallery.gallery.datas_cache;
+    gallery.gallery.thumbnails_cache.retain(|id, _| datas_cache.contains_key(id));
+
+    let mut count = 0;
+    let mut reclaimed = 0;
+    let previews_dir = get_previews_dir(&gallery.thumbnails_dir);
+    for dir in [&gallery.thumbnails_dir, &previews_dir] {
+        let Ok(entries) = read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+            let id = if dir == &previews_dir { get_preview_picture_id(stem) } else { stem };
+            if !path.is_file() || datas_cache.contains_key(id) {
+                continue;
+            }
+            let size = entry.metadata().map_or(0, |metadata| metadata.len());
+            if remove_file(&path).is_ok() {
+                count += 1;
+                reclaimed += size;
+            }
+        }
+    }
+    for entry in read_dir(get_tiles_dir(&gallery.thumbnails_dir)).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let id = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || datas_cache.contains_key(&id) {
+            continue;
+        }
+        let size = get_dir_size(&path);
+        if remove_dir_all(&path).is_ok() {
+            count += 1;
+            reclaimed += size;
+        }
+    }
+    info!("🖼️ Deleted {} orphaned thumbnails, reclaiming {} bytes", count, reclaimed);
+    (count, reclaimed)
 }
 
 const SUPPORTED_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "bmp", "webp"];
 pub fn is_supported_img_ext(ext: &OsStr) -> bool {
     SUPPORTED_EXTENSIONS.iter().any(|e| *e == ext.to_str().unwrap_or_default().to_lowercase())
 }
+pub fn get_image_mimetype(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
 pub fn is_supported_img(path: PathBuf) -> bool {
     if let Some(extension) = path.extension() {
         is_supported_img_ext(extension)
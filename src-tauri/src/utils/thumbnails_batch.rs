@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::info;
+use tauri::{AppHandle, Manager, Window, Wry};
+
+use pm_common::thumbnails::{ThumbnailPriority, ThumbnailsBatchProgress};
+
+use crate::app_data::AppDataState;
+use crate::gallery::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+use super::thumbnails::enforce_thumbnails_cache_limit;
+use super::thumbnails_queue::{process_job, ThumbnailJob, ThumbnailsQueueState};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+// Thumbnails batch generations run in a background thread per window.
+// The directory being processed is saved in the gallery so that the batch resumes when the gallery is opened again.
+#[derive(Default)]
+pub struct ThumbnailsBatchState {
+    batches: Mutex<HashMap<String, Arc<AtomicBool>>>, // Cancel flag in function of the window label
+}
+
+impl ThumbnailsBatchState {
+    // Starts the batch saved in the gallery of the window, stopping the previous one if any.
+    pub fn start(&self, app: &AppHandle<Wry>, window_label: String) {
+        self.stop(&window_label);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.batches.lock().unwrap().insert(window_label.clone(), cancel.clone());
+
+        let app = app.clone();
+        thread::spawn(move || run_batch(app, window_label, cancel));
+    }
+    // Stops the batch without clearing it from the gallery: it will be resumed at next opening.
+    pub fn stop(&self, window_label: &str) {
+        if let Some(cancel) = self.batches.lock().unwrap().remove(window_label) {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    }
+    fn on_finished(&self, window_label: &str, cancel: &Arc<AtomicBool>) {
+        let mut batches = self.batches.lock().unwrap();
+        if batches.get(window_label).is_some_and(|current| Arc::ptr_eq(current, cancel)) {
+            batches.remove(window_label);
+        }
+    }
+}
+
+fn run_batch(app: AppHandle<Wry>, window_label: String, cancel: Arc<AtomicBool>) {
+    let galleries_state = app.state::<WindowsGalleriesState>();
+    let queue_state = app.state::<ThumbnailsQueueState>();
+    let ids = {
+        let galleries = galleries_state.get_galleries();
+        let Some(gallery) = galleries.iter().find(|gallery| gallery.window_label == window_label) else {
+            return;
+        };
+        let Some(dir) = &gallery.gallery.thumbnails_batch else {
+            return;
+        };
+        gallery
+            .gallery
+            .paths_cache
+            .get_dir(dir)
+            .map(|dir| dir.get_pictures_recursive())
+            .unwrap_or_default()
+    };
+    info!(
+        "🖼️ Starting thumbnails batch generation of {} pictures for window {}",
+        ids.len(),
+        window_label
+    );
+
+    let start = Instant::now();
+    let mut last_progress = Instant::now();
+    let mut progress = ThumbnailsBatchProgress {
+        total: ids.len(),
+        ..Default::default()
+    };
+
+    for id in ids {
+        // The window might have been closed or the batch cancelled in the meantime
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(window) = app.get_window(&window_label) else {
+            return;
+        };
+
+        // Only pictures without an up to date thumbnail need to be processed.
+        // Jobs are claimed from the thumbnails queue so that a thumbnail is never generated or converted twice at the same time.
+        if let Some(job) = ThumbnailJob::new(&galleries_state, &window_label, &id, ThumbnailPriority::Background) {
+            if !job.has_up_to_date_thumbnail(&galleries_state) && queue_state.claim(&job) {
+                let generation_start = Instant::now();
+                process_job(&app, &queue_state, job);
+
+                // Sleeping in proportion of the generation time to respect the CPU usage limit.
+                let limit = app.state::<AppDataState>().data().settings.thumbnails_batch_cpu_limit.clamp(1, 100) as u32;
+                thread::sleep(generation_start.elapsed() * (100 - limit) / limit);
+            }
+        }
+
+        progress.done += 1;
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let remaining = (progress.total - progress.done) as u32;
+            progress.eta = Some((start.elapsed() / progress.done as u32 * remaining).as_secs());
+            let _ = window.emit("thumbnails-batch-progress", progress.clone());
+        }
+    }
+
+    info!("🖼️ Thumbnails batch generation finished for window {}", window_label);
+    if let Some(gallery) = galleries_state
+        .get_galleries()
+        .iter_mut()
+        .find(|gallery| gallery.window_label == window_label)
+    {
+        gallery.gallery.thumbnails_batch = None;
+    }
+    app.state::<ThumbnailsBatchState>().on_finished(&window_label, &cancel);
+    let limit = app.state::<AppDataState>().data().settings.thumbnails_cache_limit;
+    enforce_thumbnails_cache_limit(&app, limit);
+
+    if let Some(window) = app.get_window(&window_label) {
+        progress.eta = Some(0);
+        progress.finished = true;
+        let _ = window.emit("thumbnails-batch-progress", progress);
+    }
+}
+
+// Generates in background the missing thumbnails of the pictures of a directory (recursively).
+// An empty dir means the whole gallery.
+#[tauri::command]
+pub fn gen_thumbnails_batch(
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    galleries_state: tauri::State<WindowsGalleriesState>,
+    batch_state: tauri::State<ThumbnailsBatchState>,
+    dir: Vec<String>,
+) {
+    {
+        let mut galleries = galleries_state.get_galleries();
+        WindowGallery::get_mut(&mut galleries, &window).gallery.thumbnails_batch = Some(dir);
+    }
+    batch_state.start(&app, window.label().to_string());
+}
+
+#[tauri::command]
+pub fn cancel_thumbnails_batch(
+    window: Window<Wry>,
+    galleries_state: tauri::State<WindowsGalleriesState>,
+    batch_state: tauri::State<ThumbnailsBatchState>,
+) {
+    batch_state.stop(window.label());
+    {
+        let mut galleries = galleries_state.get_galleries();
+        WindowGallery::get_mut(&mut galleries, &window).gallery.thumbnails_batch = None;
+    }
+    let _ = window.emit(
+        "thumbnails-batch-progress",
+        ThumbnailsBatchProgress {
+            finished: true,
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+use log::{info, warn};
+use tauri::{AppHandle, Manager, Window, Wry};
+
+use pm_common::gallery::GallerySettings;
+use pm_common::gallery_cache::Orientation;
+use pm_common::thumbnails::ThumbnailPriority;
+
+use crate::gallery::gallery_cache::{ThumbnailCache, ThumbnailFingerprint};
+use crate::gallery::windows_galleries::WindowsGalleriesState;
+
+use super::thumbnails::{check_existing_thumbnail, find_up_to_date_thumbnail, gen_thumbnail, get_thumbnail_fingerprint, GeneratedThumbnail};
+
+pub const THUMBNAIL_HEIGHT: u32 = 280;
+const MAX_WORKERS: usize = 4;
+
+// Thumbnails are generated by a bounded pool of worker threads, picking the pending job with the highest priority.
+#[derive(Default)]
+pub struct ThumbnailsQueueState {
+    queue: Mutex<ThumbnailsQueue>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct ThumbnailsQueue {
+    pending: Vec<ThumbnailJob>,
+    running: HashSet<(String, String)>, // Window label and picture uid of the jobs being processed
+    workers: usize,
+    next_order: u64,
+}
+
+pub struct ThumbnailJob {
+    window_label: String,
+    id: String,
+    priority: ThumbnailPriority,
+    order: u64, // Jobs with the same priority are processed in request order
+    gallery_path: String,
+    image_path: String,
+    thumbnails_dir: PathBuf,
+    orientation: Orientation,
+    fingerprint: ThumbnailFingerprint,
+    settings: GallerySettings,
+    cache: Option<ThumbnailCache>, // Cache of the existing thumbnail when the job was created
+}
+
+impl ThumbnailJob {
+    // Copies what is needed to generate the thumbnail of a picture, so that the galleries lock is not held during the generation.
+    // Returns None if the gallery is closed or if the picture is unknown or unreadable.
+    pub fn new(galleries_state: &WindowsGalleriesState, window_label: &str, id: &str, priority: ThumbnailPriority) -> Option<Self> {
+        let galleries = galleries_state.get_galleries();
+        let gallery = galleries.iter().find(|gallery| gallery.window_label == window_label)?;
+        let picture = gallery.gallery.datas_cache.get(id)?;
+        let fingerprint = get_thumbnail_fingerprint(&PathBuf::from(&gallery.path).join(picture.get_path()), picture.orientation)?;
+        Some(Self {
+            window_label: window_label.to_string(),
+            id: id.to_string(),
+            priority,
+            order: 0,
+            gallery_path: gallery.path.clone(),
+            image_path: picture.get_path(),
+            thumbnails_dir: gallery.thumbnails_dir.clone(),
+            orientation: picture.orientation,
+            fingerprint,
+            settings: gallery.gallery.settings.clone(),
+            cache: gallery.gallery.thumbnails_cache.get(id).cloned(),
+        })
+    }
+    fn is(&self, window_label: &str, id: &str) -> bool {
+        self.window_label == window_label && self.id == id
+    }
+
+    // Returns true if an up-to-date thumbnail already exists, recording its cache if it changed.
+    // Outdated thumbnails are removed and old PNG thumbnails converted, which can take a while: only called by the thumbnails workers and batch.
+    pub fn has_existing_thumbnail(&self, galleries_state: &WindowsGalleriesState) -> bool {
+        let existing = check_existing_thumbnail(&self.thumbnails_dir, &self.id, &self.settings, self.cache.as_ref(), &self.fingerprint);
+        self.record_existing_thumbnail(galleries_state, existing)
+    }
+    // Same as has_existing_thumbnail, without decoding nor removing anything: false if the thumbnail must be checked by a worker.
+    pub fn has_up_to_date_thumbnail(&self, galleries_state: &WindowsGalleriesState) -> bool {
+        let existing = find_up_to_date_thumbnail(&self.thumbnails_dir, &self.id, &self.settings, self.cache.as_ref(), &self.fingerprint);
+        self.record_existing_thumbnail(galleries_state, existing)
+    }
+    fn record_existing_thumbnail(&self, galleries_state: &WindowsGalleriesState, existing: Option<ThumbnailCache>) -> bool {
+        let Some(existing) = existing else {
+            return false;
+        };
+        if self.cache.as_ref() != Some(&existing) {
+            if let Some(gallery) = galleries_state
+                .get_galleries()
+                .iter_mut()
+                .find(|gallery| gallery.window_label == self.window_label)
+            {
+                gallery.gallery.thumbnails_cache.insert(self.id.clone(), existing);
+            }
+        }
+        true
+    }
+}
+
+impl ThumbnailsQueueState {
+    fn push(&self, app: &AppHandle<Wry>, mut job: ThumbnailJob) {
+        let mut queue = self.queue.lock().unwrap();
+
+        // Identical requests are deduplicated, only updating the priority.
+        if queue.running.contains(&(job.window_label.clone(), job.id.clone())) {
+            return;
+        }
+        if let Some(pending) = queue.pending.iter_mut().find(|pending| pending.is(&job.window_label, &job.id)) {
+            pending.priority = job.priority;
+            return;
+        }
+
+        job.order = queue.next_order;
+        queue.next_order += 1;
+        queue.pending.push(job);
+
+        let max_workers = thread::available_parallelism().map_or(1, |n| n.get() - 1).clamp(1, MAX_WORKERS);
+        if queue.workers < max_workers {
+            queue.workers += 1;
+            let app = app.clone();
+            thread::spawn(move || work(app));
+        }
+        self.condvar.notify_one();
+    }
+
+    // Takes a job out of the queue to process it in the calling thread.
+    // Returns false if the same job is already being processed, its thumbnail-generated event will be emitted anyway.
+    pub fn claim(&self, job: &ThumbnailJob) -> bool {
+        let mut queue = self.queue.lock().unwrap();
+        if !queue.running.insert((job.window_label.clone(), job.id.clone())) {
+            return false;
+        }
+        queue.pending.retain(|pending| !pending.is(&job.window_label, &job.id));
+        true
+    }
+
+    pub fn update_priority(&self, window_label: &str, id: &str, priority: ThumbnailPriority) {
+        if let Some(pending) = self.queue.lock().unwrap().pending.iter_mut().find(|pending| pending.is(window_label, id)) {
+            pending.priority = priority;
+        }
+    }
+
+    // Jobs that are already being processed can't be cancelled.
+    pub fn cancel(&self, window_label: &str, id: &str) {
+        self.queue.lock().unwrap().pending.retain(|job| !job.is(window_label, id));
+    }
+    pub fn cancel_window(&self, window_label: &str) {
+        self.queue.lock().unwrap().pending.retain(|job| job.window_label != window_label);
+    }
+
+    fn next_job(&self) -> ThumbnailJob {
+        let mut queue = self.queue.lock().unwrap();
+        while queue.pending.is_empty() {
+            queue = self.condvar.wait(queue).unwrap();
+        }
+        let (index, _) = queue
+            .pending
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.priority.cmp(&b.priority).then(b.order.cmp(&a.order)))
+            .unwrap();
+        let job = queue.pending.swap_remove(index);
+        queue.running.insert((job.window_label.clone(), job.id.clone()));
+        job
+    }
+}
+
+fn work(app: AppHandle<Wry>) {
+    let queue_state = app.state::<ThumbnailsQueueState>();
+    loop {
+        let job = queue_state.next_job();
+        process_job(&app, &queue_state, job);
+    }
+}
+
+// Generates the thumbnail of a job that has been taken out of the queue (see next_job and claim).
+// The existing thumbnail is checked again first: it might only need to be converted from PNG.
+pub fn process_job(app: &AppHandle<Wry>, queue_state: &ThumbnailsQueueState, job: ThumbnailJob) {
+    let existing = job.has_existing_thumbnail(&app.state::<WindowsGalleriesState>());
+    let result = if existing {
+        None
+    } else {
+        gen_thumbnail(
+            &job.gallery_path,
+            &job.image_path,
+            &job.thumbnails_dir,
+            &job.id,
+            job.orientation,
+            THUMBNAIL_HEIGHT,
+            &job.settings,
+        )
+    };
+    if !existing && result.is_none() {
+        warn!("Unable to generate thumbnail for picture {}", job.id);
+    }
+
+    queue_state
+        .queue
+        .lock()
+        .unwrap()
+        .running
+        .remove(&(job.window_label.clone(), job.id.clone()));
+
+    // The window might have been closed in the meantime
+    if let Some(window) = app.get_window(&job.window_label) {
+        if existing {
+            let _ = window.emit("thumbnail-generated", (job.id, true));
+        } else {
+            on_thumbnail_generated(app, &window, job.id, result, job.fingerprint, &job.settings);
+        }
+    }
+}
+
+// Records the thumbnail cache and notifies the frontend that the thumbnail is ready or could not be generated.
+fn on_thumbnail_generated(
+    app: &AppHandle<Wry>,
+    window: &Window<Wry>,
+    id: String,
+    result: Option<GeneratedThumbnail>,
+    fingerprint: ThumbnailFingerprint,
+    settings: &GallerySettings,
+) {
+    if let Some(thumbnail) = &result {
+        let galleries_state = app.state::<WindowsGalleriesState>();
+        let mut galleries = galleries_state.get_galleries();
+        // The gallery might have been closed while the thumbnail was generated
+        let Some(gallery) = galleries.iter_mut().find(|gallery| gallery.window_label == window.label()) else {
+            return;
+        };
+        gallery.gallery.thumbnails_cache.insert(
+            id.clone(),
+            ThumbnailCache::new(thumbnail.source, fingerprint, thumbnail.transparent, settings),
+        );
+        // Pictures without embedded preview get their placeholder color once their thumbnail is generated
+        if let Some(picture) = gallery.gallery.datas_cache.get_mut(&id) {
+            picture.color.get_or_insert(thumbnail.color);
+        }
+    }
+    let _ = window.emit("thumbnail-generated", (id, result.is_some()));
+}
+
+// Returns true if the thumbnail already exists. Otherwise, the thumbnail-generated event is emitted once it has been generated.
+// Called for every displayed thumbnail: only cheap checks are done here, anything else is left to the workers.
+#[tauri::command]
+pub async fn request_thumbnail(
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    galleries_state: tauri::State<'_, WindowsGalleriesState>,
+    queue_state: tauri::State<'_, ThumbnailsQueueState>,
+    id: String,
+    priority: ThumbnailPriority,
+) -> Result<bool, ()> {
+    let Some(job) = ThumbnailJob::new(&galleries_state, window.label(), &id, priority) else {
+        info!("🖼️ Requested thumbnail of unknown or unreadable picture {}", id);
+        let _ = window.emit("thumbnail-generated", (id, false));
+        return Ok(false);
+    };
+    if job.has_up_to_date_thumbnail(&galleries_state) {
+        return Ok(true);
+    }
+    queue_state.push(&app, job);
+    Ok(false)
+}
+
+#[tauri::command]
+pub fn update_thumbnail_priority(window: Window<Wry>, queue_state: tauri::State<ThumbnailsQueueState>, id: String, priority: ThumbnailPriority) {
+    queue_state.update_priority(window.label(), &id, priority);
+}
+
+#[tauri::command]
+pub fn cancel_thumbnail(window: Window<Wry>, queue_state: tauri::State<ThumbnailsQueueState>, id: String) {
+    queue_state.cancel(window.label(), &id);
+}
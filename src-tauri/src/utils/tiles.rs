@@ -0,0 +1,185 @@
+use std::fs::{create_dir_all, metadata, read, remove_dir_all, write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use fast_image_resize as fr;
+use image::{ColorType, DynamicImage, RgbImage, RgbaImage};
+use log::{info, warn};
+use tauri::{State, Window, Wry};
+
+use pm_common::gallery::{GallerySettings, ThumbnailsFormat};
+use pm_common::gallery_cache::Orientation;
+use pm_common::thumbnails::TilesInfo;
+
+use crate::gallery::windows_galleries::{WindowGallery, WindowsGalleriesState};
+
+use super::thumbnails::{encode_thumbnail, is_transparent, load_oriented_image, resize_image};
+
+// Tile pyramids are stored in a sub directory of the thumbnails directory: `tiles/<uid>/<level>/<x>_<y>.<ext>`.
+// The info file is written last, a pyramid without it is incomplete.
+const TILES_DIR: &str = "tiles";
+const INFO_FILE: &str = "info.json";
+const TILE_SIZE: u32 = 512;
+const TILES_QUALITY: u8 = 85;
+
+// Building a pyramid needs the whole picture decoded in memory: only one pyramid is built at a time.
+static BUILD_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct TilesJob {
+    img_path: PathBuf,
+    tiles_dir: PathBuf,
+    orientation: Orientation,
+    settings: GallerySettings,
+}
+
+impl TilesJob {
+    // Returns None if the picture is unknown. The gallery lock must be released before running the job.
+    pub fn new(gallery: &WindowGallery, id: &str) -> Option<Self> {
+        let picture = gallery.gallery.datas_cache.get(id)?;
+        Some(Self {
+            img_path: PathBuf::from(&gallery.path).join(picture.get_path()),
+            tiles_dir: get_picture_tiles_dir(&gallery.thumbnails_dir, id),
+            orientation: picture.orientation,
+            settings: gallery.gallery.settings.clone(),
+        })
+    }
+
+    // Returns the pyramid of the picture, building it if it does not exist or is older than the picture.
+    pub fn run(&self) -> Option<TilesInfo> {
+        if let Some(info) = self.read_info() {
+            return Some(info);
+        }
+        let _lock = BUILD_LOCK.lock().unwrap();
+        // The pyramid might have been built while waiting for the lock
+        if let Some(info) = self.read_info() {
+            return Some(info);
+        }
+        let info = self.build();
+        if info.is_none() {
+            let _ = remove_dir_all(&self.tiles_dir);
+        }
+        info
+    }
+
+    fn read_info(&self) -> Option<TilesInfo> {
+        let info_path = self.tiles_dir.join(INFO_FILE);
+        let picture_modified = metadata(&self.img_path).and_then(|metadata| metadata.modified()).ok()?;
+        let info_modified = metadata(&info_path).and_then(|metadata| metadata.modified()).ok()?;
+        if info_modified < picture_modified {
+            return None;
+        }
+        serde_json::from_slice(&read(info_path).ok()?).ok()
+    }
+
+    fn build(&self) -> Option<TilesInfo> {
+        let start = std::time::Instant::now();
+        let _ = remove_dir_all(&self.tiles_dir);
+
+        // No embedded preview is that high: the original is always decoded.
+        let (img, _) = load_oriented_image(&self.img_path, self.orientation, u32::MAX)?;
+        let transparent = is_transparent(&img);
+        let mut img = if transparent {
+            DynamicImage::ImageRgba8(img.into_rgba8())
+        } else {
+            DynamicImage::ImageRgb8(img.into_rgb8())
+        };
+
+        let mut info = TilesInfo {
+            width: img.width(),
+            height: img.height(),
+            tile_size: TILE_SIZE,
+            levels: 0,
+            format: match self.settings.thumbnails_format {
+                _ if transparent => ThumbnailsFormat::Png,
+                ThumbnailsFormat::Png => ThumbnailsFormat::Jpeg,
+                format => format,
+            },
+        };
+        // Levels are downscaled from the previous one, without sharpening that would accumulate.
+        let settings = GallerySettings {
+            thumbnails_sharpening: false,
+            ..self.settings.clone()
+        };
+
+        loop {
+            self.write_level_tiles(&img, &info)?;
+            info.levels += 1;
+            if img.width() <= TILE_SIZE && img.height() <= TILE_SIZE {
+                break;
+            }
+            let (width, height) = info.get_level_dimensions(info.levels);
+            let (src_width, src_height) = (img.width(), img.height());
+            let pixel_type = if transparent { fr::PixelType::U8x4 } else { fr::PixelType::U8x3 };
+            let pixels = resize_image(img.into_bytes(), src_width, src_height, pixel_type, width, height, &settings)?;
+            img = if transparent {
+                DynamicImage::ImageRgba8(RgbaImage::from_raw(width, height, pixels)?)
+            } else {
+                DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, pixels)?)
+            };
+        }
+
+        write(self.tiles_dir.join(INFO_FILE), serde_json::to_vec(&info).ok()?).ok()?;
+        info!(
+            "🖼️ Building a pyramid of {} levels for picture {:?} took {:?}",
+            info.levels,
+            self.img_path,
+            start.elapsed()
+        );
+        Some(info)
+    }
+
+    fn write_level_tiles(&self, img: &DynamicImage, info: &TilesInfo) -> Option<()> {
+        let level_dir = self.tiles_dir.join(info.levels.to_string());
+        create_dir_all(&level_dir).ok()?;
+        let color_type = if info.format == ThumbnailsFormat::Png {
+            ColorType::Rgba8
+        } else {
+            ColorType::Rgb8
+        };
+        let (columns, rows) = info.get_level_tiles(info.levels);
+        for y in 0..rows {
+            for x in 0..columns {
+                let (left, top) = (x * TILE_SIZE, y * TILE_SIZE);
+                let tile = img.crop_imm(left, top, TILE_SIZE.min(img.width() - left), TILE_SIZE.min(img.height() - top));
+                let data = encode_thumbnail(tile.as_bytes(), tile.width(), tile.height(), color_type, info.format, TILES_QUALITY)?;
+                let path = level_dir.join(format!("{}_{}.{}", x, y, info.format.extension()));
+                if let Err(e) = write(&path, data) {
+                    warn!("Unable to write tile {:?}, error: {}", path, e);
+                    return None;
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+pub fn get_tiles_dir(thumbnails_dir: &Path) -> PathBuf {
+    thumbnails_dir.join(TILES_DIR)
+}
+fn get_picture_tiles_dir(thumbnails_dir: &Path, id: &str) -> PathBuf {
+    get_tiles_dir(thumbnails_dir).join(id)
+}
+
+// Returns the path and mimetype of a tile of an already built pyramid.
+pub fn get_existing_tile(thumbnails_dir: &Path, id: &str, level: u32, x: u32, y: u32) -> Option<(PathBuf, &'static str)> {
+    let level_dir = get_picture_tiles_dir(thumbnails_dir, id).join(level.to_string());
+    ThumbnailsFormat::ALL
+        .iter()
+        .map(|format| (level_dir.join(format!("{}_{}.{}", x, y, format.extension())), format.mimetype()))
+        .find(|(path, _)| path.exists())
+}
+
+// Builds the tile pyramid of the picture in background if needed.
+// Returns the pyramid description, its tiles can then be loaded through /get-tile, or None if it could not be built.
+#[tauri::command]
+pub async fn request_tiles(window: Window<Wry>, galleries_state: State<'_, WindowsGalleriesState>, id: String) -> Result<Option<TilesInfo>, ()> {
+    let job = {
+        let galleries = galleries_state.get_galleries();
+        TilesJob::new(WindowGallery::get(&galleries, &window), &id)
+    };
+    let Some(job) = job else {
+        info!("🖼️ Requested tiles of unknown picture {}", id);
+        return Ok(None);
+    };
+    Ok(tauri::async_runtime::spawn_blocking(move || job.run()).await.ok().flatten())
+}
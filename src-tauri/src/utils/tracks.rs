@@ -0,0 +1,367 @@
+use std::{fs, path::Path};
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde_json::Value;
+
+use crate::utils::exif_utils::parse_xmp_date;
+
+// Timed points of GPS tracks, read from GPX, KML or GeoJSON files.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    pub time: i64, // UTC, in seconds since UNIX epoch
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+}
+
+// Reads the timed points of a track file, the format being guessed from its extension. Points without time are ignored.
+pub fn read_track_file(path: &Path) -> Result<Vec<TrackPoint>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "gpx" => read_gpx(&content),
+        "kml" => read_kml(&content),
+        "geojson" | "json" => read_geojson(&content),
+        _ => Err(format!("unsupported track format: {}", extension)),
+    }
+}
+
+// Location at the given UTC time, interpolated between the two surrounding points.
+// Points must be sorted by time. Points further than max_gap seconds are not used.
+// The altitude is None if the points have none.
+pub fn locate_in_track(points: &[TrackPoint], time: i64, max_gap: i64) -> Option<(f64, f64, Option<f64>)> {
+    let index = points.partition_point(|point| point.time < time);
+    let (previous, next) = (index.checked_sub(1).and_then(|i| points.get(i)), points.get(index));
+    match (previous, next) {
+        (_, Some(next)) if next.time == time => Some(to_location(next)),
+        (Some(previous), Some(next)) if next.time - previous.time <= max_gap => {
+            let ratio = (time - previous.time) as f64 / (next.time - previous.time) as f64;
+            let interpolate = |a: f64, b: f64| a + (b - a) * ratio;
+            // Longitudes are interpolated the short way, across the antimeridian if needed
+            let next_longitude = match next.longitude - previous.longitude {
+                delta if delta > 180.0 => next.longitude - 360.0,
+                delta if delta < -180.0 => next.longitude + 360.0,
+                _ => next.longitude,
+            };
+            Some((
+                interpolate(previous.latitude, next.latitude),
+                (interpolate(previous.longitude, next_longitude) + 180.0).rem_euclid(360.0) - 180.0,
+                match (previous.altitude, next.altitude) {
+                    (Some(a), Some(b)) => Some(interpolate(a, b)),
+                    (a, b) => a.or(b),
+                },
+            ))
+        }
+        // Before, after or in a hole of the track: the nearest point is used if close enough
+        _ => [previous, next]
+            .into_iter()
+            .flatten()
+            .map(|point| (point, (point.time - time).abs()))
+            .filter(|(_, gap)| *gap <= max_gap)
+            .min_by_key(|(_, gap)| *gap)
+            .map(|(point, _)| to_location(point)),
+    }
+}
+
+fn to_location(point: &TrackPoint) -> (f64, f64, Option<f64>) {
+    (point.latitude, point.longitude, point.altitude)
+}
+
+// Track points (trkpt) and route points (rtept), with their <ele> and <time> children.
+// Waypoints (wpt) are points of interest rather than recorded positions, they are ignored.
+fn read_gpx(content: &str) -> Result<Vec<TrackPoint>, String> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+    let mut points = Vec::new();
+    let mut point: Option<(f64, f64, Option<f64>, Option<i64>)> = None;
+    let mut element = Vec::new();
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) => {
+                element = e.local_name().as_ref().to_vec();
+                if matches!(element.as_slice(), b"trkpt" | b"rtept") {
+                    let (mut latitude, mut longitude) = (None, None);
+                    for attribute in e.attributes().flatten() {
+                        let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+                        match attribute.key.local_name().as_ref() {
+                            b"lat" => latitude = value.trim().parse::<f64>().ok(),
+                            b"lon" => longitude = value.trim().parse::<f64>().ok(),
+                            _ => {}
+                        }
+                    }
+                    point = latitude.zip(longitude).map(|(latitude, longitude)| (latitude, longitude, None, None));
+                }
+            }
+            Event::Text(e) => {
+                if let Some((_, _, altitude, time)) = &mut point {
+                    let text = e.unescape().map_err(|e| e.to_string())?;
+                    match element.as_slice() {
+                        b"ele" => *altitude = text.trim().parse::<f64>().ok(),
+                        b"time" => *time = parse_track_time(&text),
+                        _ => {}
+                    }
+                }
+            }
+            Event::End(e) => {
+                if matches!(e.local_name().as_ref(), b"trkpt" | b"rtept") {
+                    if let Some((latitude, longitude, altitude, Some(time))) = point.take() {
+                        points.push(TrackPoint {
+                            time,
+                            latitude,
+                            longitude,
+                            altitude,
+                        });
+                    }
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sorted(points))
+}
+
+// Google Earth tracks (<gx:Track> with <when> and <gx:coord> lists) and timestamped placemarks (<TimeStamp> and <Point>).
+fn read_kml(content: &str) -> Result<Vec<TrackPoint>, String> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+    let mut points = Vec::new();
+    let mut element = Vec::new();
+    let (mut in_track, mut in_point) = (false, false);
+    let (mut whens, mut coords) = (Vec::new(), Vec::new()); // Of the current track, or of the current placemark
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) => {
+                element = e.local_name().as_ref().to_vec();
+                in_point |= element == b"Point";
+                if matches!(element.as_slice(), b"Track" | b"Placemark") {
+                    in_track = element == b"Track";
+                    whens.clear();
+                    coords.clear();
+                }
+            }
+            Event::Text(e) => {
+                let text = e.unescape().map_err(|e| e.to_string())?;
+                match element.as_slice() {
+                    b"when" => whens.push(parse_track_time(&text)),
+                    // Space separated "lon lat alt" in tracks
+                    b"coord" if in_track => coords.push(parse_coordinates(text.split_whitespace())),
+                    // Comma separated "lon,lat,alt" in points, only the first tuple is used
+                    b"coordinates" if in_point => coords.push(text.split_whitespace().next().and_then(|tuple| parse_coordinates(tuple.split(',')))),
+                    _ => {}
+                }
+            }
+            Event::End(e) => {
+                in_point &= e.local_name().as_ref() != b"Point";
+                // A placemark is a single point, unlike a track which has as many whens as coords
+                if matches!(e.local_name().as_ref(), b"Track" | b"Placemark") {
+                    for (time, coordinates) in whens.drain(..).zip(coords.drain(..)) {
+                        if let (Some(time), Some((longitude, latitude, altitude))) = (time, coordinates) {
+                            points.push(TrackPoint {
+                                time,
+                                latitude,
+                                longitude,
+                                altitude,
+                            });
+                        }
+                    }
+                    in_track = false;
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sorted(points))
+}
+
+// LineString and MultiLineString features with their times in a coordTimes or times property (as exported by togeojson),
+// and Point features with a time or timestamp property.
+fn read_geojson(content: &str) -> Result<Vec<TrackPoint>, String> {
+    let json: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let features = match json["type"].as_str() {
+        Some("FeatureCollection") => json["features"].as_array().cloned().unwrap_or_default(),
+        Some("Feature") => vec![json],
+        _ => return Err("not a GeoJSON feature or feature collection".to_string()),
+    };
+
+    let mut points = Vec::new();
+    let mut add_points = |coordinates: &Value, times: &Value| {
+        let (Some(coordinates), Some(times)) = (coordinates.as_array(), times.as_array()) else {
+            return;
+        };
+        for (coordinates, time) in coordinates.iter().zip(times) {
+            let values = coordinates.as_array().into_iter().flatten().map(|value| value.to_string());
+            if let (Some(time), Some((longitude, latitude, altitude))) = (time.as_str().and_then(parse_track_time), parse_coordinates(values)) {
+                points.push(TrackPoint {
+                    time,
+                    latitude,
+                    longitude,
+                    altitude,
+                });
+            }
+        }
+    };
+    for feature in features {
+        let geometry = &feature["geometry"];
+        let properties = &feature["properties"];
+        let times = if properties["coordTimes"].is_array() {
+            &properties["coordTimes"]
+        } else {
+            &properties["times"]
+        };
+        match geometry["type"].as_str() {
+            Some("LineString") => add_points(&geometry["coordinates"], times),
+            Some("MultiLineString") => {
+                for (line, line_times) in geometry["coordinates"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .zip(times.as_array().into_iter().flatten())
+                {
+                    add_points(line, line_times);
+                }
+            }
+            Some("Point") => {
+                let time = if properties["time"].is_string() {
+                    &properties["time"]
+                } else {
+                    &properties["timestamp"]
+                };
+                add_points(&Value::Array(vec![geometry["coordinates"].clone()]), &Value::Array(vec![time.clone()]));
+            }
+            _ => {}
+        }
+    }
+    Ok(sorted(points))
+}
+
+// Longitude, latitude and optional altitude, in this order like in KML and GeoJSON.
+fn parse_coordinates<S: AsRef<str>>(mut values: impl Iterator<Item = S>) -> Option<(f64, f64, Option<f64>)> {
+    let longitude = values.next()?.as_ref().trim().parse::<f64>().ok()?;
+    let latitude = values.next()?.as_ref().trim().parse::<f64>().ok()?;
+    let altitude = values.next().and_then(|altitude| altitude.as_ref().trim().parse::<f64>().ok());
+    Some((longitude, latitude, altitude))
+}
+
+// ISO 8601 date, UTC if no timezone is specified.
+fn parse_track_time(time: &str) -> Option<i64> {
+    let (date, offset) = parse_xmp_date(time.trim())?;
+    Some(date.assume_utc().unix_timestamp() - offset.unwrap_or(0) as i64)
+}
+
+fn sorted(mut points: Vec<TrackPoint>) -> Vec<TrackPoint> {
+    points.sort_by_key(|point| point.time);
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIME: i64 = 1681552800; // 2023-04-15T10:00:00Z
+
+    fn point(time: i64, latitude: f64, longitude: f64, altitude: Option<f64>) -> TrackPoint {
+        TrackPoint {
+            time,
+            latitude,
+            longitude,
+            altitude,
+        }
+    }
+
+    #[test]
+    fn locate_in_track_interpolates_between_points() {
+        let points = [point(TIME, 10.0, 20.0, Some(100.0)), point(TIME + 100, 20.0, 40.0, Some(200.0))];
+        assert_eq!(locate_in_track(&points, TIME, 60), Some((10.0, 20.0, Some(100.0))));
+        assert_eq!(locate_in_track(&points, TIME + 50, 300), Some((15.0, 30.0, Some(150.0))));
+    }
+
+    #[test]
+    fn locate_in_track_keeps_unknown_altitude() {
+        let points = [point(TIME, 10.0, 20.0, None), point(TIME + 100, 20.0, 40.0, Some(200.0))];
+        assert_eq!(locate_in_track(&points, TIME + 50, 300), Some((15.0, 30.0, Some(200.0))));
+        let points = [point(TIME, 10.0, 20.0, None), point(TIME + 100, 20.0, 40.0, None)];
+        assert_eq!(locate_in_track(&points, TIME + 50, 300), Some((15.0, 30.0, None)));
+    }
+
+    #[test]
+    fn locate_in_track_crosses_the_antimeridian() {
+        let points = [point(TIME, 0.0, 179.0, None), point(TIME + 100, 0.0, -179.0, None)];
+        let (_, longitude, _) = locate_in_track(&points, TIME + 25, 300).unwrap();
+        assert!((longitude - 179.5).abs() < 1e-9);
+        let (_, longitude, _) = locate_in_track(&points, TIME + 75, 300).unwrap();
+        assert!((longitude + 179.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn locate_in_track_uses_the_nearest_point_in_gaps() {
+        let points = [point(TIME, 10.0, 20.0, None), point(TIME + 1000, 20.0, 40.0, None)];
+        // In a hole of the track, only a close enough point is used
+        assert_eq!(locate_in_track(&points, TIME + 100, 300), Some((10.0, 20.0, None)));
+        assert_eq!(locate_in_track(&points, TIME + 900, 300), Some((20.0, 40.0, None)));
+        assert_eq!(locate_in_track(&points, TIME + 500, 300), None);
+        // Before and after the track
+        assert_eq!(locate_in_track(&points, TIME - 60, 300), Some((10.0, 20.0, None)));
+        assert_eq!(locate_in_track(&points, TIME + 2000, 300), None);
+        assert_eq!(locate_in_track(&[], TIME, 300), None);
+    }
+
+    #[test]
+    fn read_gpx_reads_track_and_route_points() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <gpx version="1.1" xmlns="http://www.topografix.com/GPX/1/1">
+                <wpt lat="1.0" lon="2.0"><time>2023-04-15T09:00:00Z</time></wpt>
+                <trk><trkseg>
+                    <trkpt lat="45.5" lon="6.25"><ele>1200.5</ele><time>2023-04-15T10:30:20Z</time></trkpt>
+                    <trkpt lat="45.6" lon="6.3"><time>2023-04-15T12:00:00+02:00</time></trkpt>
+                    <trkpt lat="45.7" lon="6.4"><ele>1300</ele></trkpt>
+                </trkseg></trk>
+                <rte><rtept lat="46" lon="7"><time>2023-04-15T11:00:00Z</time></rtept></rte>
+            </gpx>"#;
+        assert_eq!(
+            read_gpx(gpx),
+            Ok(vec![
+                point(TIME, 45.6, 6.3, None),
+                point(TIME + 1820, 45.5, 6.25, Some(1200.5)),
+                point(TIME + 3600, 46.0, 7.0, None),
+            ])
+        );
+    }
+
+    #[test]
+    fn read_kml_reads_tracks_and_timestamped_placemarks() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+                <Document>
+                    <Placemark>
+                        <gx:Track>
+                            <when>2023-04-15T10:00:00Z</when>
+                            <when>2023-04-15T10:30:20Z</when>
+                            <gx:coord>6.3 45.6 1250</gx:coord>
+                            <gx:coord>6.25 45.5</gx:coord>
+                        </gx:Track>
+                    </Placemark>
+                    <Placemark>
+                        <TimeStamp><when>2023-04-15T11:00:00Z</when></TimeStamp>
+                        <Point><coordinates>7,46,500</coordinates></Point>
+                    </Placemark>
+                    <Placemark>
+                        <Point><coordinates>8,47</coordinates></Point>
+                    </Placemark>
+                </Document>
+            </kml>"#;
+        assert_eq!(
+            read_kml(kml),
+            Ok(vec![
+                point(TIME, 45.6, 6.3, Some(1250.0)),
+                point(TIME + 1820, 45.5, 6.25, None),
+                point(TIME + 3600, 46.0, 7.0, Some(500.0)),
+            ])
+        );
+    }
+}
@@ -14,10 +14,12 @@ use yewdux::store::Store;
 
 use pm_common::app_data::{Settings, Theme};
 use pm_common::gallery::{GalleryData, GallerySettings};
+use pm_common::ratings::RatingFilter;
+use pm_common::tags::get_pictures_tags;
 
 use crate::header::header::Header;
 use crate::leftbar::leftbar::LeftBar;
-use crate::mainpane::mainpane::MainPane;
+use crate::mainpane::mainpane::{CacheContext, MainPane};
 use crate::rightbar::rightbar::RightBar;
 use crate::utils::translator::Translator;
 use crate::utils::utils::{cmd_async, cmd_async_get};
@@ -52,6 +54,7 @@ pub struct Context {
     pub theme: Theme,
     pub gallery_path: String,
     pub main_pane_content: MainPaneDisplayType,
+    pub main_pane_all_pictures: Vec<String>, // Pictures of the displayed directory, before filtering
     pub main_pane_pictures: Vec<String>,
     pub main_pane_dirs: Vec<String>,
     pub main_pane_selected_index: Option<usize>,
@@ -111,6 +114,24 @@ impl Context {
         }
     }
 
+    // Filters the pictures of the main pane with the fast filter and the hierarchy tags filters.
+    // The selection is reset if the displayed pictures changed.
+    pub fn filter_pictures(&mut self, filter: &RatingFilter, cache: &CacheContext) {
+        let pictures_tags = get_pictures_tags(&cache.tag_groups);
+        let pictures: Vec<String> = self
+            .main_pane_all_pictures
+            .iter()
+            .filter(|id| filter.matches(&cache.ratings.get(*id).copied().unwrap_or_default()))
+            .filter(|id| cache.hierarchy.matches_tags(pictures_tags.get(*id).map_or(&[][..], Vec::as_slice)))
+            .cloned()
+            .collect();
+        if pictures != self.main_pane_pictures {
+            self.main_pane_pictures = pictures;
+            self.main_pane_selected_index = None;
+            self.main_pane_selected_indices = Vec::new();
+        }
+    }
+
     pub fn get_selected_picture_ids(&self) -> Vec<String> {
         if let Some(i) = self.main_pane_selected_index {
             if self.main_pane_selected_indices.len() > 1 {
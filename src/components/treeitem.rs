@@ -81,6 +81,13 @@ impl Component for TreeItem {
                     payload: ctx.props().id.clone(),
                     ..Default::default()
                 });
+                menu.add_separator();
+                menu.add_item(MenuItem {
+                    label: "Generate thumbnails".to_string(),
+                    event: "contex_menu_tree_item_thumbnails".to_string(),
+                    payload: self.path.join("/"),
+                    ..Default::default()
+                });
                 menu.show();
                 false
             }
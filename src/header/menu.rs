@@ -81,6 +81,7 @@ pub fn get_menus(t: &Rc<Translator>) -> Vec<MenuItem> {
                 ],
             ),
             MenuItem::new_item_acc("new_gallery".to_string(), t.tr("menu-bar-file-new-gallery"), "Ctrl+N".to_string()),
+            MenuItem::new_item_acc("export".to_string(), t.tr("menu-bar-file-export"), "Ctrl+E".to_string()),
             MenuItem::new_separator(0),
             MenuItem::new_item_acc("close_window".to_string(), t.tr("menu-bar-file-close-gallery"), "Ctrl+W".to_string()),
             MenuItem::new_item_acc("quit".to_string(), t.tr("menu-bar-file-quit"), "Ctrl+Q".to_string()),
@@ -108,6 +109,12 @@ pub fn get_menus(t: &Rc<Translator>) -> Vec<MenuItem> {
         vec![
             MenuItem::new_item("update_gallery".to_string(), t.tr("menu-bar-tools-update-gallery")),
             MenuItem::new_item("edit_exif".to_string(), t.tr("menu-bar-tools-edit-exif")),
+            MenuItem::new_item("shift_dates".to_string(), t.tr("menu-bar-tools-shift-dates")),
+            MenuItem::new_item("geotag".to_string(), t.tr("menu-bar-tools-geotag")),
+            MenuItem::new_separator(4),
+            MenuItem::new_item("gen_thumbnails".to_string(), t.tr("menu-bar-tools-gen-thumbnails")),
+            MenuItem::new_item("regen_thumbnails".to_string(), t.tr("menu-bar-tools-regen-thumbnails")),
+            MenuItem::new_item("clean_thumbnails".to_string(), t.tr("menu-bar-tools-clean-thumbnails")),
         ],
     ));
     menus
@@ -0,0 +1,84 @@
+use yew::{classes, function_component, html, Callback, Html};
+use yewdux::prelude::{use_selector, use_store, Dispatch};
+
+use pm_common::gallery::GalleryData;
+use pm_common::ratings::{ColorLabel, PictureFlag, RatingFilter};
+
+use crate::mainpane::rating_badges::get_label_class;
+use crate::utils::translator::Translator;
+
+/// Filters the pictures of the main pane by minimum stars, flag and color label.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn FastFilters() -> Html {
+    let (t, _) = use_store::<Translator>();
+    let filter = use_selector(|data: &GalleryData| data.rating_filter.clone());
+
+    let onclear = Callback::from(|_| Dispatch::<GalleryData>::global().reduce_mut(|data| data.rating_filter = RatingFilter::default()));
+
+    let stars_buttons = (0..=5u8)
+        .map(|stars| {
+            let onclick = Callback::from(move |_| Dispatch::<GalleryData>::global().reduce_mut(|data| data.rating_filter.min_stars = stars));
+            let text = if stars == 0 {
+                t.tr("fast-filter-any")
+            } else {
+                format!("{}+", "★".repeat(stars as usize))
+            };
+            html! {
+                <button class={classes!(if filter.min_stars == stars { Some("selected") } else { None })} {onclick}>{text}</button>
+            }
+        })
+        .collect::<Html>();
+
+    let flags_buttons = [
+        (PictureFlag::Pick, "fast-filter-pick"),
+        (PictureFlag::None, "fast-filter-unflagged"),
+        (PictureFlag::Reject, "fast-filter-rejected"),
+    ]
+    .into_iter()
+    .map(|(flag, key)| {
+        let onclick = Callback::from(move |_| {
+            Dispatch::<GalleryData>::global().reduce_mut(|data| toggle(&mut data.rating_filter.flags, flag));
+        });
+        html! {
+            <button class={classes!(if filter.flags.contains(&flag) { Some("selected") } else { None })} {onclick}>{t.tr(key)}</button>
+        }
+    })
+    .collect::<Html>();
+
+    let labels_buttons = ColorLabel::ALL
+        .into_iter()
+        .map(|label| {
+            let onclick = Callback::from(move |_| {
+                Dispatch::<GalleryData>::global().reduce_mut(|data| toggle(&mut data.rating_filter.labels, label));
+            });
+            html! {
+                <button class={classes!("label", get_label_class(label), if filter.labels.contains(&label) { Some("selected") } else { None })}
+                    title={label.xmp_label()} {onclick}></button>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="fast-filters">
+            <h4>{t.tr("fast-filter-stars")}</h4>
+            <div class="buttons">{stars_buttons}</div>
+            <h4>{t.tr("fast-filter-flag")}</h4>
+            <div class="buttons">{flags_buttons}</div>
+            <h4>{t.tr("fast-filter-label")}</h4>
+            <div class="buttons labels">{labels_buttons}</div>
+            <button class="clear" disabled={filter.is_empty()} onclick={onclear}>
+                {t.tr("fast-filter-clear")}
+            </button>
+        </div>
+    }
+}
+
+// Adds the value to the list, or removes it if already present.
+fn toggle<T: PartialEq>(values: &mut Vec<T>, value: T) {
+    if let Some(index) = values.iter().position(|v| *v == value) {
+        values.remove(index);
+    } else {
+        values.push(value);
+    }
+}
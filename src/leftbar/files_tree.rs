@@ -88,9 +88,11 @@ pub fn FilesTree() -> Html {
             let pictures = path_cache.pictures.clone();
             let dirs: Vec<String> = path_cache.children.iter().map(|child| child.dir_name.clone()).collect();
 
+            let filter = data_dispatch.get().rating_filter.clone();
             ctx_dispatch.reduce_mut(|ctx| {
                 ctx.main_pane_content = MainPaneDisplayType::PicturesAndDirs((*selected_dir).clone());
-                ctx.main_pane_pictures = pictures;
+                ctx.main_pane_all_pictures = pictures;
+                ctx.filter_pictures(&filter, &cache);
                 ctx.main_pane_dirs = dirs;
             });
         }
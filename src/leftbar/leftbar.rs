@@ -4,7 +4,9 @@ use yewdux::prelude::{use_selector, Dispatch};
 
 use pm_common::gallery::GalleryData;
 
+use crate::leftbar::fast_filters::FastFilters;
 use crate::leftbar::files_tree::FilesTree;
+use crate::leftbar::thumbnails_batch::ThumbnailsBatch;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -57,7 +59,7 @@ pub fn LeftBar() -> Html {
                 }else if *selected_tab == 2 {
                     html! {
                         <div class="content">
-                            {"Fast filtering view"}
+                            <FastFilters />
                         </div>
                     }
                 }else{
@@ -68,6 +70,7 @@ pub fn LeftBar() -> Html {
                     }
                 }
             }
+            <ThumbnailsBatch />
         </section>
     }
 }
@@ -1,2 +1,4 @@
+mod fast_filters;
 mod files_tree;
 pub mod leftbar;
+mod thumbnails_batch;
@@ -0,0 +1,100 @@
+use fluent::FluentArgs;
+use futures::stream::StreamExt;
+use serde::{Deserialize, Serialize};
+use tauri_sys::event::listen;
+use tauri_sys::window::current_window;
+use yew::platform::spawn_local;
+use yew::{function_component, html, use_state, Callback, Html};
+use yew_hooks::use_is_first_mount;
+use yew_icons::{Icon, IconId};
+use yewdux::prelude::use_store;
+
+use pm_common::thumbnails::ThumbnailsBatchProgress;
+
+use crate::utils::translator::Translator;
+use crate::utils::utils::{cmd, cmd_arg};
+
+#[derive(Serialize, Deserialize)]
+struct GenThumbnailsBatchArgs {
+    dir: Vec<String>,
+}
+
+/// Footer of the left bar showing the progress of the background thumbnails generation,
+/// or the result of the last thumbnails cleaning.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn ThumbnailsBatch() -> Html {
+    let (t, _) = use_store::<Translator>();
+    let progress = use_state(|| None::<ThumbnailsBatchProgress>);
+    let cleaned = use_state(|| None::<(usize, u64)>); // Deleted thumbnails count and reclaimed bytes
+
+    if use_is_first_mount() {
+        let progress = progress.clone();
+        let cleaned = cleaned.clone();
+        spawn_local(async move {
+            let mut events = listen::<ThumbnailsBatchProgress>("thumbnails-batch-progress").await.unwrap();
+            while let Some(e) = events.next().await {
+                progress.set(if e.payload.finished { None } else { Some(e.payload) });
+            }
+        });
+        spawn_local(async move {
+            let mut events = listen::<(usize, u64)>("thumbnails-cleaned").await.unwrap();
+            while let Some(e) = events.next().await {
+                cleaned.set(Some(e.payload));
+            }
+        });
+        // Generating the thumbnails of a directory from the files tree context menu
+        spawn_local(async move {
+            let mut events = listen::<String>("contex_menu_tree_item_thumbnails").await.unwrap();
+            while let Some(e) = events.next().await {
+                if e.window_label == Some(current_window().label()) {
+                    let dir = e.payload.split('/').map(|dir| dir.to_string()).collect();
+                    cmd_arg("gen_thumbnails_batch", &GenThumbnailsBatchArgs { dir });
+                }
+            }
+        });
+    }
+
+    let Some(progress) = &*progress else {
+        let Some((count, reclaimed)) = *cleaned else {
+            return html! {};
+        };
+        let mut args = FluentArgs::new();
+        args.set("count", count);
+        args.set("size", format!("{:.1}", reclaimed as f64 / 1_000_000.0));
+        return html! {
+            <div class="thumbnails-batch">
+                <div class="info">
+                    <p>{t.tra("thumbnails-cleaned", &args)}</p>
+                </div>
+                <button title={t.tr("thumbnails-cleaned-close")} onclick={Callback::from(move |_| cleaned.set(None))}>
+                    <Icon icon_id={IconId::LucideX}/>
+                </button>
+            </div>
+        };
+    };
+
+    let mut args = FluentArgs::new();
+    args.set("done", progress.done);
+    args.set("total", progress.total);
+    let eta = progress.eta.map(|eta| {
+        let mut args = FluentArgs::new();
+        args.set("minutes", eta / 60);
+        args.set("seconds", eta % 60);
+        t.tra("thumbnails-batch-eta", &args)
+    });
+    let percent = if progress.total == 0 { 0 } else { progress.done * 100 / progress.total };
+
+    html! {
+        <div class="thumbnails-batch">
+            <div class="info">
+                <p>{t.tra("thumbnails-batch-progress", &args)}</p>
+                <p>{eta.unwrap_or_default()}</p>
+            </div>
+            <button title={t.tr("thumbnails-batch-cancel")} onclick={Callback::from(|_| cmd("cancel_thumbnails_batch"))}>
+                <Icon icon_id={IconId::LucideX}/>
+            </button>
+            <progress max="100" value={percent.to_string()}></progress>
+        </div>
+    }
+}
@@ -1,16 +1,22 @@
 use js_sys::Math::abs;
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use web_sys::{HtmlElement, WheelEvent};
+use web_sys::{window, HtmlElement, WheelEvent};
 use yew::suspense::Suspense;
 use yew::{
-    function_component, html, suspense::use_future_with, use_context, use_effect_with, use_node_ref, use_state, Callback, HtmlResult, NodeRef,
-    Properties,
+    function_component, html, suspense::use_future_with, use_context, use_effect_with, use_node_ref, use_state, Callback, Event, Html, HtmlResult,
+    NodeRef, Properties,
 };
 use yew_hooks::{use_is_first_mount, use_size, use_update};
 
+use pm_common::thumbnails::TilesInfo;
+
+use crate::utils::thumbnails::{use_placeholder_style, use_preview, use_tiles};
 use crate::{app::StaticContext, utils::utils::cmd_async};
 
+// Pictures larger than this (in pixels) are displayed with tiles when zoomed past their preview.
+const TILED_MIN_PIXELS: u64 = 50_000_000;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetImageArgs {
     pub(crate) id: String,
@@ -39,6 +45,7 @@ pub fn FullPicture(props: &Props) -> HtmlResult {
     let ref_container = use_node_ref();
     let left_s = use_state(|| 0f64);
     let top_s = use_state(|| 0f64);
+    let scroll = use_state(|| (0, 0));
 
     // Force component to re-render when the container size change.
     let (container_width, container_height) = use_size(ref_container.clone());
@@ -89,10 +96,20 @@ pub fn FullPicture(props: &Props) -> HtmlResult {
             })
         };
 
+        let onscroll = {
+            let container = ref_container.clone();
+            let scroll = scroll.clone();
+            Callback::from(move |_: Event| {
+                if let Some(container) = container.cast::<HtmlElement>() {
+                    scroll.set((container.scroll_left(), container.scroll_top()));
+                }
+            })
+        };
+
         return Ok(html! {
             <Suspense fallback={fallback}>
-                <div class="full-image" ref={ref_container.clone()} {onwheel}>
-                    <FullPictureImage id={props.id.clone()} {width} {height} zoom={*zoom} {ref_container} {container_width} {container_height}/>
+                <div class="full-image" ref={ref_container.clone()} {onwheel} {onscroll}>
+                    <FullPictureImage id={props.id.clone()} {width} {height} zoom={*zoom} {ref_container} {container_width} {container_height} scroll={*scroll}/>
                 </div>
             </Suspense>
         });
@@ -112,6 +129,8 @@ pub struct ImageProps {
     // Needs to be passed as a prop to force re-render when the container size change.
     pub container_width: u32,
     pub container_height: u32,
+    // Needs to be passed as a prop to load the visible tiles when scrolling.
+    pub scroll: (i32, i32),
 }
 
 #[allow(non_snake_case)]
@@ -119,6 +138,12 @@ pub struct ImageProps {
 fn FullPictureImage(props: &ImageProps) -> HtmlResult {
     let static_ctx = use_context::<StaticContext>().unwrap();
 
+    // The preview fits the container in device pixels, the original is only loaded when zooming past the preview resolution.
+    let pixel_ratio = window().map_or(1.0, |window| window.device_pixel_ratio());
+    let max = (props.container_width.max(props.container_height) as f64 * pixel_ratio) as u32;
+    let preview = use_preview(props.id.clone(), max);
+    let placeholder = use_placeholder_style(props.id.clone());
+
     let update = use_update();
     let is_first_mount = use_is_first_mount();
     let ref_image = use_node_ref();
@@ -126,14 +151,12 @@ fn FullPictureImage(props: &ImageProps) -> HtmlResult {
     let container = props.ref_container.cast::<HtmlElement>();
     let image = ref_image.cast::<HtmlElement>();
 
-    if is_first_mount {
-        update();
-        // Force a first empty render to initialize node ref
-        return Ok(html! { <div class="image"></div> });
-    }
-
     let mut left = 0;
     let mut top = 0;
+    // Without preview, the original is loaded as before.
+    let mut load_original = preview == Some(None);
+    let mut displayed_width = 0.0;
+    let mut visible = (0.0, 0.0, 1.0, 1.0);
     if container.is_some() && image.is_some() {
         let container = container.unwrap();
         let image = image.unwrap();
@@ -143,6 +166,10 @@ fn FullPictureImage(props: &ImageProps) -> HtmlResult {
         let img_w = image.client_width() as f64;
         let img_h = image.client_height() as f64;
 
+        if let Some(Some(size)) = preview {
+            load_original = img_w.max(img_h) * props.zoom * pixel_ratio > size as f64;
+        }
+
         if cont_w > img_w * props.zoom {
             info!("left = {}", ((cont_w - img_w * props.zoom) / 2.0));
             left = ((cont_w - img_w * props.zoom) / 2.0) as i32;
@@ -150,15 +177,112 @@ fn FullPictureImage(props: &ImageProps) -> HtmlResult {
         if cont_h > img_h * props.zoom {
             top = ((cont_h - img_h * props.zoom) / 2.0) as i32;
         }
+
+        // Visible area in fractions of the picture, the image container being scaled from its top left corner.
+        if img_w > 0.0 && img_h > 0.0 {
+            let scroll_left = container.scroll_left() as f64 - left as f64;
+            let scroll_top = container.scroll_top() as f64 - top as f64;
+            visible = (
+                scroll_left / props.zoom / img_w,
+                scroll_top / props.zoom / img_h,
+                (scroll_left + cont_w) / props.zoom / img_w,
+                (scroll_top + cont_h) / props.zoom / img_h,
+            );
+        }
+        displayed_width = img_w * props.zoom * pixel_ratio;
+    }
+
+    // Very large pictures are loaded by tiles instead of decoding the whole original.
+    let tiled = props.width as u64 * props.height as u64 > TILED_MIN_PIXELS;
+    let tiles = use_tiles(props.id.clone(), tiled && load_original);
+
+    if is_first_mount {
+        update();
+        // Force a first empty render to initialize node ref
+        return Ok(html! { <div class="image"></div> });
+    }
+
+    let tiles = match tiles {
+        Some(Some(info)) if load_original => {
+            load_original = false;
+            get_visible_tiles(&info, displayed_width, visible)
+                .into_iter()
+                .map(|(level, x, y, (left, top, width, height))| {
+                    html! {
+                        <div class="tile"
+                            style={format!("background-image: url({}/get-tile?id={}&window={}&level={}&x={}&y={}); left: {}%; top: {}%; width: {}%; height: {}%;",
+                                static_ctx.protocol, props.id, static_ctx.window_label, level, x, y, left, top, width, height)}>
+                        </div>
+                    }
+                })
+                .collect::<Html>()
+        }
+        // The original is only loaded if the pyramid could not be built.
+        _ => {
+            load_original &= !tiled || tiles == Some(None);
+            html! {}
+        }
+    };
+
+    // The original is layered over the preview, which stays visible while the original loads.
+    let mut layers = Vec::new();
+    if load_original {
+        layers.push(format!(
+            "url({}/get-image?id={}&window={})",
+            static_ctx.protocol, props.id, static_ctx.window_label
+        ));
     }
+    if let Some(Some(size)) = preview {
+        layers.push(format!(
+            "url({}/get-preview?id={}&window={}&max={})",
+            static_ctx.protocol, props.id, static_ctx.window_label, size
+        ));
+    }
+    let layers = if layers.is_empty() { "none".to_string() } else { layers.join(", ") };
 
     Ok(html! {
         <div class="image-container" ref={ref_image}
             style={format!("aspect-ratio: {}/{}; scale: {}; left: {}px; top: {}px; padding: {}px", props.width, props.height, props.zoom, left, top, 3f64 / props.zoom)}>
             <div class="image"
-                style={format!("background-image: url({}/get-image?id={}&window={}); border-radius: {}px",
-                    static_ctx.protocol, props.id, static_ctx.window_label, 3f64 / props.zoom)}>
+                style={format!("background-image: {}; border-radius: {}px; {}", layers, 3f64 / props.zoom, placeholder)}>
+                {tiles}
             </div>
         </div>
     })
 }
+
+// Returns the tiles of the level matching the displayed width (in device pixels) that intersect the visible area.
+// Tiles are returned with their level, column, row, and their position and size in percents of the picture.
+fn get_visible_tiles(info: &TilesInfo, displayed_width: f64, visible: (f64, f64, f64, f64)) -> Vec<(u32, u32, u32, (f64, f64, f64, f64))> {
+    let level = (info.width as f64 / displayed_width.max(1.0))
+        .log2()
+        .floor()
+        .clamp(0.0, info.levels.saturating_sub(1) as f64) as u32;
+    let (width, height) = info.get_level_dimensions(level);
+    let (columns, rows) = info.get_level_tiles(level);
+    let tile_size = info.tile_size as f64;
+
+    let range = |start: f64, end: f64, length: u32, count: u32| {
+        let first = (start.max(0.0) * length as f64 / tile_size).floor() as u32;
+        let last = ((end.max(0.0) * length as f64 / tile_size).ceil() as u32).min(count);
+        first..last
+    };
+    let mut tiles = Vec::new();
+    for y in range(visible.1, visible.3, height, rows) {
+        for x in range(visible.0, visible.2, width, columns) {
+            let (tile_left, tile_top) = (x * info.tile_size, y * info.tile_size);
+            tiles.push((
+                level,
+                x,
+                y,
+                (
+                    tile_left as f64 * 100.0 / width as f64,
+                    tile_top as f64 * 100.0 / height as f64,
+                    info.tile_size.min(width - tile_left) as f64 * 100.0 / width as f64,
+                    info.tile_size.min(height - tile_top) as f64 * 100.0 / height as f64,
+                ),
+            ));
+        }
+    }
+    tiles
+}
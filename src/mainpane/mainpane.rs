@@ -6,17 +6,24 @@ use tauri_sys::event::listen;
 use web_sys::HtmlElement;
 use yew::platform::spawn_local;
 use yew::suspense::use_future;
-use yew::{function_component, html, use_node_ref, use_state, Callback, Children, Html, Properties, Suspense};
-use yew_hooks::use_size;
+use yew::{function_component, html, use_effect_with, use_node_ref, use_state, Callback, Children, Html, Properties, Suspense};
+use yew_hooks::{use_is_first_mount, use_size};
 use yewdux::prelude::{use_selector, use_store, Dispatch};
 use yewdux::store::Store;
 
+use pm_common::gallery::GalleryData;
 use pm_common::gallery_cache::{PathsCache, PictureCache};
+use pm_common::hierarchy_config::HierarchyConfig;
+use pm_common::ratings::PictureRating;
+use pm_common::tags::TagGroup;
 
 use crate::app::MainPaneDisplayType;
 use crate::app::{Context, MainPaneDimensions};
 use crate::mainpane::picture_and_carousel::PictureAndCarousel;
 use crate::mainpane::pictures_list::PicturesList;
+use crate::utils::ratings::{listen_ratings_events, use_rating_shortcuts};
+use crate::utils::tags::listen_tags_events;
+use crate::utils::thumbnails::listen_thumbnails_events;
 use crate::utils::utils::cmd_async_get;
 
 #[derive(Clone, Debug, Default, PartialEq, Store)]
@@ -24,6 +31,9 @@ pub struct CacheContext {
     pub is_loaded: bool,
     pub datas_cache: HashMap<String, PictureCache>,
     pub paths_cache: PathsCache,
+    pub ratings: HashMap<String, PictureRating>, // Stored in the gallery, in function of the picture uid
+    pub tag_groups: HashMap<String, TagGroup>,   // Stored in the gallery, by id
+    pub hierarchy: HierarchyConfig,              // Stored in the gallery
 }
 
 #[derive(Properties, PartialEq)]
@@ -45,6 +55,9 @@ pub fn MainPane() -> Html {
                 is_loaded: true,
                 datas_cache: cmd_async_get::<HashMap<String, PictureCache>>("get_gallery_datas_cache").await,
                 paths_cache: cmd_async_get::<PathsCache>("get_gallery_paths_cache").await,
+                ratings: cmd_async_get::<HashMap<String, PictureRating>>("get_gallery_ratings").await,
+                tag_groups: cmd_async_get::<HashMap<String, TagGroup>>("get_gallery_tag_groups").await,
+                hierarchy: cmd_async_get::<HierarchyConfig>("get_gallery_hierarchy").await,
             });
         })
     };
@@ -55,15 +68,34 @@ pub fn MainPane() -> Html {
                 .await
                 .unwrap();
             while let Some(e) = events.next().await {
-                cache_dispatch.set(CacheContext {
-                    is_loaded: true,
-                    datas_cache: e.payload.0,
-                    paths_cache: e.payload.1,
+                cache_dispatch.reduce_mut(|cache| {
+                    cache.is_loaded = true;
+                    cache.datas_cache = e.payload.0;
+                    cache.paths_cache = e.payload.1;
                 });
             }
         }
     });
 
+    if use_is_first_mount() {
+        listen_thumbnails_events();
+        listen_ratings_events();
+        listen_tags_events();
+    }
+    use_rating_shortcuts();
+
+    // Filtering the pictures again when the fast filter, the tags or the hierarchy filters change
+    let rating_filter = use_selector(|data: &GalleryData| data.rating_filter.clone());
+    let tag_groups = use_selector(|cache: &CacheContext| cache.tag_groups.clone());
+    let hierarchy = use_selector(|cache: &CacheContext| cache.hierarchy.clone());
+    {
+        let context_dispatch = context_dispatch.clone();
+        use_effect_with((rating_filter, tag_groups, hierarchy), move |(rating_filter, _, _)| {
+            let cache = Dispatch::<CacheContext>::global().get();
+            context_dispatch.reduce_mut(|context| context.filter_pictures(rating_filter, &cache));
+        });
+    }
+
     // Syncing main pane dimensions to Context
     let node = use_node_ref();
     let on_scroll_zone_changed = {
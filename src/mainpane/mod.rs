@@ -5,3 +5,4 @@ pub mod picture_and_carousel;
 pub mod picture_carousel;
 pub mod picture_thumb;
 pub mod pictures_list;
+pub mod rating_badges;
@@ -65,9 +65,14 @@ pub fn PictureAndCarousel(_: &PictureAndCarouselProps) -> Html {
         })
     };
 
+    // All the pictures might have been filtered out
+    let Some(id) = pictures_ids.get(selected_index) else {
+        return html! { <div class="empty"></div> };
+    };
+
     html! {
         <div class="picture-and-carousel">
-            <FullPicture id={pictures_ids[selected_index].clone()} />
+            <FullPicture id={id.clone()} />
             <div class="carousel-container">
                 <div class="carousel-overflow">
                     <div class="carousel">
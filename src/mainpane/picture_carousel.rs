@@ -2,14 +2,18 @@ use log::warn;
 use web_sys::HtmlElement;
 use yew::suspense::Suspense;
 use yew::{
-    classes, function_component, html, suspense::use_future_with, use_context, use_effect, use_node_ref, use_state_eq, Callback, HtmlResult,
+    classes, function_component, html, suspense::use_future_with, use_context, use_effect, use_node_ref, use_state_eq, Callback, Html, HtmlResult,
     Properties,
 };
 use yew_hooks::use_size;
 use yewdux::Dispatch;
 
+use pm_common::thumbnails::ThumbnailPriority;
+
 use crate::app::Context;
 use crate::mainpane::full_picture::GetImageArgs;
+use crate::mainpane::rating_badges::RatingBadges;
+use crate::utils::thumbnails::{use_placeholder_style, use_thumbnail};
 use crate::{app::StaticContext, utils::utils::cmd_async};
 
 #[derive(Properties, PartialEq)]
@@ -87,7 +91,8 @@ pub fn PictureCarousel(props: &Props) -> HtmlResult {
         return Ok(html! {
             <Suspense fallback={fallback}>
                 <li onclick={onclick} ref={li_ref}>
-                    <PictureCarouselImage id={props.id.clone()} width={carousel_height*width/height} height={carousel_height} />
+                    <PictureCarouselImage id={props.id.clone()} width={carousel_height*width/height} height={carousel_height} selected={props.selected} />
+                    <RatingBadges id={props.id.clone()}/>
                     {
                         if props.selected {
                             html! {
@@ -111,26 +116,37 @@ pub struct ImageProps {
     pub id: String,
     pub width: u32,
     pub height: u32,
+    pub selected: bool,
 }
 
 #[allow(non_snake_case)]
 #[function_component]
-fn PictureCarouselImage(props: &ImageProps) -> HtmlResult {
+fn PictureCarouselImage(props: &ImageProps) -> Html {
     let static_ctx = use_context::<StaticContext>().unwrap();
 
-    let has_thumb = use_future_with(props.id.clone(), |id| async move {
-        cmd_async::<GetImageArgs, bool>("gen_image_thumbnail", &GetImageArgs { id: id.to_string() }).await
-    })?;
+    let priority = if props.selected {
+        ThumbnailPriority::Visible
+    } else {
+        ThumbnailPriority::Nearby
+    };
+    let has_thumb = use_thumbnail(props.id.clone(), priority);
+    let placeholder = use_placeholder_style(props.id.clone());
 
-    if !*has_thumb {
-        warn!("No thumb for {}", props.id);
-        return Ok(html! {});
+    match has_thumb {
+        None => html! {
+            <div class="image" style={format!("width: {}px; height: {}px; {}", props.width, props.height, placeholder)}></div>
+        },
+        Some(false) => {
+            warn!("No thumb for {}", props.id);
+            html! {}
+        }
+        Some(true) => html! {
+            <div class="image" style={format!("width: {}px; height: {}px; {}", props.width, props.height, placeholder)}>
+                <div class="thumb-image"
+                    style={format!("background-image: url({}/get-thumbnail?id={}&window={});",
+                    static_ctx.protocol, props.id, static_ctx.window_label)}>
+                </div>
+            </div>
+        },
     }
-
-    Ok(html! {
-        <div class="image"
-            style={format!("background-image: url({}/get-thumbnail?id={}&window={}); width: {}px; height: {}px;",
-            static_ctx.protocol, props.id, static_ctx.window_label, props.width, props.height)}>
-        </div>
-    })
 }
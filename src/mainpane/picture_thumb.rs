@@ -2,13 +2,17 @@ use log::warn;
 use web_sys::MouseEvent;
 use yew::suspense::Suspense;
 use yew::use_node_ref;
-use yew::{function_component, html, suspense::use_future_with, use_context, Callback, HtmlResult, Properties};
+use yew::{function_component, html, suspense::use_future_with, use_context, Callback, Html, HtmlResult, Properties};
 use yew_hooks::{use_is_first_mount, use_size, use_update};
 use yewdux::prelude::use_selector;
 use yewdux::Dispatch;
 
+use pm_common::thumbnails::ThumbnailPriority;
+
 use crate::app::Context;
 use crate::mainpane::full_picture::GetImageArgs;
+use crate::mainpane::rating_badges::RatingBadges;
+use crate::utils::thumbnails::{use_placeholder_style, use_thumbnail};
 use crate::utils::utils::get_non_null_ref;
 use crate::{app::StaticContext, utils::utils::cmd_async};
 
@@ -41,6 +45,7 @@ pub fn PictureThumb(props: &Props) -> HtmlResult {
 
     // Force component to re-render when the loading element size changes (gets defined).
     let _ = use_size(ref_load.clone());
+    let placeholder = use_placeholder_style(props.id.clone());
 
     if let Some((width, height)) = *dimensions {
         let h = 140;
@@ -66,7 +71,7 @@ pub fn PictureThumb(props: &Props) -> HtmlResult {
 
         let fallback = html! {
             <li class="loading" style={format!("flex-basis: {}px; flex-grow: {};", w, w)} ref={ref_load.clone()} onclick={onclick.clone()}>
-                <div class="thumb" style={format!("aspect-ratio: {} / {};", w, h)} />
+                <div class="thumb" style={format!("aspect-ratio: {} / {}; {}", w, h, placeholder)} />
             </li>
         };
 
@@ -79,23 +84,31 @@ pub fn PictureThumb(props: &Props) -> HtmlResult {
         // Not displaying image if not in the visible area
         let element = get_non_null_ref(ref_load.clone(), ref_pic.clone());
         let mut visible = false;
+        let mut nearby = false;
         if let Some(el) = element {
             let top = el.offset_top();
             let height = el.offset_height();
             if main_pane_dimensions.scroll_bottom != 0 && top != 0 && height != 0 {
+                visible = top + height >= main_pane_dimensions.scroll_top && top <= main_pane_dimensions.scroll_bottom;
                 // Add 300 px margin to add scroll smoothness
-                visible = top + height >= main_pane_dimensions.scroll_top - 300 && top <= main_pane_dimensions.scroll_bottom + 300;
+                nearby = top + height >= main_pane_dimensions.scroll_top - 300 && top <= main_pane_dimensions.scroll_bottom + 300;
             }
         }
-        if !visible {
+        if !nearby {
             return Ok(fallback);
         }
+        let priority = if visible {
+            ThumbnailPriority::Visible
+        } else {
+            ThumbnailPriority::Nearby
+        };
 
         return Ok(html! {
             <Suspense fallback={fallback}>
                 <li style={format!("flex-basis: {}px; flex-grow: {};", w, w)}
                     ref={ref_pic.clone()} onclick={onclick.clone()} ondblclick={ondblclick.clone()}>
-                    <PictureThumbImage id={props.id.clone()} width={w} height={h}/>
+                    <PictureThumbImage id={props.id.clone()} width={w} height={h} {priority}/>
+                    <RatingBadges id={props.id.clone()}/>
                     {
                         if is_selected {
                             html! {
@@ -119,26 +132,32 @@ pub struct ImageProps {
     pub id: String,
     pub width: u32,
     pub height: u32,
+    pub priority: ThumbnailPriority,
 }
 
 #[allow(non_snake_case)]
 #[function_component]
-fn PictureThumbImage(props: &ImageProps) -> HtmlResult {
+fn PictureThumbImage(props: &ImageProps) -> Html {
     let static_ctx = use_context::<StaticContext>().unwrap();
 
-    let has_thumb = use_future_with(props.id.clone(), |id| async move {
-        cmd_async::<GetImageArgs, bool>("gen_image_thumbnail", &GetImageArgs { id: id.to_string() }).await
-    })?;
+    let has_thumb = use_thumbnail(props.id.clone(), props.priority);
+    let placeholder = use_placeholder_style(props.id.clone());
 
-    if !*has_thumb {
-        warn!("No thumb for {}", props.id);
-        return Ok(html! {});
+    match has_thumb {
+        None => html! {
+            <div class="thumb" style={format!("aspect-ratio: {} / {}; {}", props.width, props.height, placeholder)}></div>
+        },
+        Some(false) => {
+            warn!("No thumb for {}", props.id);
+            html! {}
+        }
+        Some(true) => html! {
+            <div class="thumb" style={format!("aspect-ratio: {} / {}; {}", props.width, props.height, placeholder)}>
+                <div class="thumb-image"
+                    style={format!("background-image: url({}/get-thumbnail?id={}&window={});",
+                    static_ctx.protocol, props.id, static_ctx.window_label)}>
+                </div>
+            </div>
+        },
     }
-
-    Ok(html! {
-        <div class="thumb"
-            style={format!("background-image: url({}/get-thumbnail?id={}&window={}); aspect-ratio: {} / {};",
-            static_ctx.protocol, props.id, static_ctx.window_label, props.width, props.height)}>
-        </div>
-    })
 }
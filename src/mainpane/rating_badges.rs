@@ -0,0 +1,57 @@
+use yew::{classes, function_component, html, Html, Properties};
+use yewdux::prelude::use_selector_with_deps;
+
+use pm_common::ratings::{ColorLabel, PictureFlag};
+
+use crate::mainpane::mainpane::CacheContext;
+
+#[derive(Properties, PartialEq)]
+pub struct RatingBadgesProps {
+    pub id: String,
+}
+
+/// Stars, flag and color label of a picture, displayed over its thumbnail.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn RatingBadges(props: &RatingBadgesProps) -> Html {
+    let rating = use_selector_with_deps(|cache: &CacheContext, id: &String| cache.ratings.get(id).copied(), props.id.clone());
+    let Some(rating) = *rating else {
+        return html! {};
+    };
+
+    html! {
+        <div class="rating-badges">
+            {
+                match rating.flag {
+                    PictureFlag::Pick => html! { <span class="flag pick">{"⚑"}</span> },
+                    PictureFlag::Reject => html! { <span class="flag reject">{"✕"}</span> },
+                    PictureFlag::None => html! {},
+                }
+            }
+            {
+                if rating.stars > 0 {
+                    html! { <span class="stars">{"★".repeat(rating.stars as usize)}</span> }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if let Some(label) = rating.label {
+                    html! { <span class={classes!("label", get_label_class(label))}></span> }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
+
+pub fn get_label_class(label: ColorLabel) -> &'static str {
+    match label {
+        ColorLabel::Red => "red",
+        ColorLabel::Yellow => "yellow",
+        ColorLabel::Green => "green",
+        ColorLabel::Blue => "blue",
+        ColorLabel::Purple => "purple",
+    }
+}
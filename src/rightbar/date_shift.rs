@@ -0,0 +1,284 @@
+use fluent::FluentArgs;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::platform::spawn_local;
+use yew::{function_component, html, use_state, Callback, Event, Html, InputEvent, Properties, SubmitEvent, TargetCast, UseStateHandle};
+use yewdux::prelude::{use_selector, use_store};
+
+use pm_common::metadata::{DateShift, DateShiftOffset, DateShiftPreview, MetadataEditError};
+
+use crate::mainpane::mainpane::CacheContext;
+use crate::rightbar::metadata_editor::{exif_date_to_input, input_date_to_exif};
+use crate::utils::translator::Translator;
+use crate::utils::utils::cmd_async_result;
+
+#[derive(Serialize, Deserialize)]
+struct DateShiftArgs {
+    ids: Vec<String>,
+    shift: DateShift,
+}
+
+#[derive(Clone, PartialEq)]
+enum Mode {
+    Offset,
+    Reference,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct DateShiftToolProps {
+    pub ids: Vec<String>,
+    pub on_close: Callback<()>,
+}
+
+/// Shifts the capture dates of the selected pictures by an offset, or aligns them to the date of a reference picture.
+/// The new dates are previewed before being written.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn DateShiftTool(props: &DateShiftToolProps) -> Html {
+    let (t, _) = use_store::<Translator>();
+    let cache = use_selector(|cache: &CacheContext| cache.datas_cache.clone());
+    let mode = use_state(|| Mode::Offset);
+    let offset = use_state(|| [String::new(), String::new(), String::new(), String::new()]); // Days, hours, minutes, seconds
+    let reference = use_state(|| props.ids.first().cloned().unwrap_or_default());
+    let reference_date = use_state(|| None::<String>); // Defaults to the current date of the reference
+    let timezone = use_state(String::new);
+    let preview = use_state(|| None::<Vec<DateShiftPreview>>);
+    let error = use_state(|| None::<String>);
+    let saving = use_state(|| false);
+
+    let reference_date_value = (*reference_date)
+        .clone()
+        .or_else(|| cache.get(&*reference).and_then(|picture| picture.date.as_deref().map(exif_date_to_input)))
+        .unwrap_or_default();
+
+    // Any change of the parameters invalidates the preview
+    let on_change = {
+        let preview = preview.clone();
+        let error = error.clone();
+        move || {
+            preview.set(None);
+            error.set(None);
+        }
+    };
+
+    let get_shift = {
+        let t = t.clone();
+        let mode = mode.clone();
+        let offset = offset.clone();
+        let reference = reference.clone();
+        let timezone = timezone.clone();
+        let reference_date_value = reference_date_value.clone();
+        move || -> Result<DateShift, String> {
+            let offset = match *mode {
+                Mode::Offset => {
+                    let mut seconds = 0;
+                    for (value, unit) in offset.iter().zip([86400, 3600, 60, 1]) {
+                        let value = value.trim();
+                        if !value.is_empty() {
+                            seconds += value.parse::<i64>().map_err(|_| t.tr("metadata-error-invalid-number"))? * unit;
+                        }
+                    }
+                    DateShiftOffset::Seconds(seconds)
+                }
+                Mode::Reference => DateShiftOffset::Reference {
+                    id: (*reference).clone(),
+                    date: input_date_to_exif(&reference_date_value),
+                },
+            };
+            let timezone = timezone.trim();
+            Ok(DateShift {
+                offset,
+                timezone: if timezone.is_empty() { None } else { Some(timezone.to_string()) },
+            })
+        }
+    };
+
+    let onpreview = {
+        let t = t.clone();
+        let ids = props.ids.clone();
+        let preview = preview.clone();
+        let error = error.clone();
+        let get_shift = get_shift.clone();
+        Callback::from(move |_| {
+            let shift = match get_shift() {
+                Ok(shift) => shift,
+                Err(e) => return error.set(Some(e)),
+            };
+            let (t, ids, preview, error) = (t.clone(), ids.clone(), preview.clone(), error.clone());
+            spawn_local(async move {
+                match cmd_async_result::<_, Vec<DateShiftPreview>, MetadataEditError>("preview_date_shift", &DateShiftArgs { ids, shift }).await {
+                    Ok(result) => preview.set(Some(result)),
+                    Err(e) => error.set(Some(t.tr(e.translation_key()))),
+                }
+            });
+        })
+    };
+
+    let onsubmit = {
+        let t = t.clone();
+        let ids = props.ids.clone();
+        let on_close = props.on_close.clone();
+        let error = error.clone();
+        let saving = saving.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let shift = match get_shift() {
+                Ok(shift) => shift,
+                Err(e) => return error.set(Some(e)),
+            };
+            let (t, ids, on_close, error, saving) = (t.clone(), ids.clone(), on_close.clone(), error.clone(), saving.clone());
+            saving.set(true);
+            spawn_local(async move {
+                let result = cmd_async_result::<_, (), MetadataEditError>("shift_pictures_dates", &DateShiftArgs { ids, shift }).await;
+                saving.set(false);
+                match result {
+                    Ok(()) => on_close.emit(()),
+                    Err(e) => {
+                        let mut args = FluentArgs::new();
+                        if let MetadataEditError::WriteFailed(paths) = &e {
+                            args.set("count", paths.len());
+                        }
+                        error.set(Some(t.tra(e.translation_key(), &args)));
+                    }
+                }
+            });
+        })
+    };
+    let oncancel = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+
+    let set_mode = |new_mode: Mode| {
+        let mode = mode.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |_| {
+            mode.set(new_mode.clone());
+            on_change();
+        })
+    };
+    let oninput_text = |state: UseStateHandle<String>| {
+        let on_change = on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            state.set(e.target_unchecked_into::<HtmlInputElement>().value());
+            on_change();
+        })
+    };
+
+    html! {
+        <form class="metadata-editor date-shift" {onsubmit}>
+            <h3>{t.tr("date-shift-title")}</h3>
+            <div class="modes">
+                <label>
+                    <input type="radio" name="date-shift-mode" checked={*mode == Mode::Offset} onchange={set_mode(Mode::Offset)}/>
+                    <span>{t.tr("date-shift-mode-offset")}</span>
+                </label>
+                <label>
+                    <input type="radio" name="date-shift-mode" checked={*mode == Mode::Reference} onchange={set_mode(Mode::Reference)}/>
+                    <span>{t.tr("date-shift-mode-reference")}</span>
+                </label>
+            </div>
+            {
+                if *mode == Mode::Offset {
+                    ["date-shift-days", "date-shift-hours", "date-shift-minutes", "date-shift-seconds"].iter().enumerate().map(|(i, key)| {
+                        let oninput = {
+                            let offset = offset.clone();
+                            let on_change = on_change.clone();
+                            Callback::from(move |e: InputEvent| {
+                                let mut new_offset = (*offset).clone();
+                                new_offset[i] = e.target_unchecked_into::<HtmlInputElement>().value();
+                                offset.set(new_offset);
+                                on_change();
+                            })
+                        };
+                        html! {
+                            <label>
+                                <span>{t.tr(key)}</span>
+                                <input type="number" step="1" placeholder="0" value={offset[i].clone()} {oninput}/>
+                            </label>
+                        }
+                    }).collect::<Html>()
+                } else {
+                    let onchange = {
+                        let reference = reference.clone();
+                        let reference_date = reference_date.clone();
+                        let on_change = on_change.clone();
+                        Callback::from(move |e: Event| {
+                            reference.set(e.target_unchecked_into::<HtmlSelectElement>().value());
+                            reference_date.set(None);
+                            on_change();
+                        })
+                    };
+                    let oninput = {
+                        let reference_date = reference_date.clone();
+                        let on_change = on_change.clone();
+                        Callback::from(move |e: InputEvent| {
+                            reference_date.set(Some(e.target_unchecked_into::<HtmlInputElement>().value()));
+                            on_change();
+                        })
+                    };
+                    html! {
+                        <>
+                            <label>
+                                <span>{t.tr("date-shift-reference")}</span>
+                                <select {onchange}>
+                                    {
+                                        props.ids.iter().filter_map(|id| cache.get(id).map(|picture| (id, picture))).map(|(id, picture)| {
+                                            let name = picture.path.rsplit('/').next().unwrap_or_default().to_string();
+                                            html! { <option value={id.clone()} selected={*id == *reference}>{name}</option> }
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                            </label>
+                            <label>
+                                <span>{t.tr("date-shift-reference-date")}</span>
+                                <input type="datetime-local" step="1" value={reference_date_value} {oninput}/>
+                            </label>
+                            <p class="hint">{t.tr("date-shift-reference-hint")}</p>
+                        </>
+                    }
+                }
+            }
+            <label>
+                <span>{t.tr("date-shift-timezone")}</span>
+                <input type="text" placeholder="+02:00" value={(*timezone).clone()} oninput={oninput_text(timezone.clone())}/>
+            </label>
+            {
+                if let Some(preview) = &*preview {
+                    let mut args = FluentArgs::new();
+                    args.set("count", preview.len());
+                    html! {
+                        <>
+                            <p>{t.tra("date-shift-preview-count", &args)}</p>
+                            <ul class="date-shift-preview">
+                                {
+                                    preview.iter().map(|change| html! {
+                                        <li key={change.id.clone()}>
+                                            <span>{exif_date_to_input(&change.old_date).replace('T', " ")}</span>
+                                            {" → "}
+                                            <span>{exif_date_to_input(&change.new_date).replace('T', " ")}</span>
+                                        </li>
+                                    }).collect::<Html>()
+                                }
+                            </ul>
+                        </>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if let Some(error) = &*error {
+                    html! { <p class="error">{error}</p> }
+                } else {
+                    html! {}
+                }
+            }
+            <div class="buttons">
+                <button type="button" onclick={oncancel}>{t.tr("metadata-cancel")}</button>
+                <button type="button" onclick={onpreview}>{t.tr("date-shift-preview")}</button>
+                <button type="submit" disabled={*saving || (*preview).as_ref().map_or(true, |preview| preview.is_empty())}>{t.tr("date-shift-apply")}</button>
+            </div>
+        </form>
+    }
+}
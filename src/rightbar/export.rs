@@ -0,0 +1,231 @@
+use fluent::FluentArgs;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::{function_component, html, use_state, Callback, Event, Html, InputEvent, Properties, SubmitEvent, TargetCast, UseStateHandle};
+use yewdux::prelude::{use_selector, use_store, Dispatch};
+
+use pm_common::gallery::GallerySettings;
+use pm_common::metadata::{Export, ExportError, HomeZone, MetadataStrip};
+
+use crate::app::GallerySettingsContainer;
+use crate::mainpane::mainpane::CacheContext;
+use crate::utils::translator::Translator;
+use crate::utils::utils::{cmd_arg, cmd_async_get, cmd_async_result};
+
+#[derive(Serialize, Deserialize)]
+struct ExportArgs {
+    ids: Vec<String>,
+    export: Export,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ExportToolProps {
+    pub ids: Vec<String>,
+    pub on_close: Callback<()>,
+}
+
+/// Copies the selected pictures to a directory, removing all their metadata or only the private parts of it.
+/// The location of the pictures taken in a home zone is always removed.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn ExportTool(props: &ExportToolProps) -> Html {
+    let (t, _) = use_store::<Translator>();
+    let cache = use_selector(|cache: &CacheContext| cache.datas_cache.clone());
+    let strip = use_selector(|settings: &GallerySettings| settings.export_strip);
+    let home_zones = use_selector(|settings: &GallerySettings| settings.home_zones.clone());
+    let destination = use_state(|| None::<String>);
+    let error = use_state(|| None::<String>);
+    let exported = use_state(|| None::<usize>);
+    let exporting = use_state(|| false);
+
+    // New home zones are centered on the first located picture by default
+    let first_location = props.ids.iter().find_map(|id| cache.get(id)?.location);
+    let zone_name = use_state(String::new);
+    let zone_latitude = use_state(|| first_location.map(|(lat, _, _)| lat.to_string()).unwrap_or_default());
+    let zone_longitude = use_state(|| first_location.map(|(_, lon, _)| lon.to_string()).unwrap_or_default());
+    let zone_radius = use_state(|| "500".to_string());
+
+    let onpickdestination = {
+        let destination = destination.clone();
+        let exported = exported.clone();
+        Callback::from(move |_| {
+            let (destination, exported) = (destination.clone(), exported.clone());
+            spawn_local(async move {
+                if let Some(path) = cmd_async_get::<Option<String>>("pick_export_dir").await {
+                    destination.set(Some(path));
+                    exported.set(None);
+                }
+            });
+        })
+    };
+
+    let onsubmit = {
+        let t = t.clone();
+        let ids = props.ids.clone();
+        let strip = strip.clone();
+        let destination = destination.clone();
+        let error = error.clone();
+        let exported = exported.clone();
+        let exporting = exporting.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let Some(destination) = (*destination).clone() else {
+                return;
+            };
+            let export = Export { destination, strip: *strip };
+            let (t, ids, error, exported, exporting) = (t.clone(), ids.clone(), error.clone(), exported.clone(), exporting.clone());
+            exporting.set(true);
+            error.set(None);
+            exported.set(None);
+            spawn_local(async move {
+                let result = cmd_async_result::<_, usize, ExportError>("export_pictures", &ExportArgs { ids, export }).await;
+                exporting.set(false);
+                match result {
+                    Ok(count) => exported.set(Some(count)),
+                    Err(e) => {
+                        let mut args = FluentArgs::new();
+                        if let ExportError::ExportFailed(paths) = &e {
+                            args.set("count", paths.len());
+                        }
+                        error.set(Some(t.tra(e.translation_key(), &args)));
+                    }
+                }
+            });
+        })
+    };
+    let oncancel = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+
+    let strip_checkbox = |key: &'static str, get: fn(&MetadataStrip) -> bool, set: fn(&mut MetadataStrip, bool)| {
+        let onchange = Callback::from(move |e: Event| {
+            let checked = e.target_unchecked_into::<HtmlInputElement>().checked();
+            Dispatch::<GallerySettings>::global().reduce_mut(|settings| set(&mut settings.export_strip, checked));
+        });
+        // Removing all the metadata includes everything else
+        let implied = key != "export-strip-all" && strip.all;
+        html! {
+            <label class="checkbox">
+                <input type="checkbox" checked={get(&strip) || implied} disabled={implied} {onchange}/>
+                <span>{t.tr(key)}</span>
+            </label>
+        }
+    };
+
+    let oninput_text =
+        |state: UseStateHandle<String>| Callback::from(move |e: InputEvent| state.set(e.target_unchecked_into::<HtmlInputElement>().value()));
+    let new_zone = match (
+        zone_latitude.trim().parse::<f64>(),
+        zone_longitude.trim().parse::<f64>(),
+        zone_radius.trim().parse::<f64>(),
+    ) {
+        (Ok(latitude), Ok(longitude), Ok(radius)) if latitude.abs() <= 90.0 && longitude.abs() <= 180.0 && radius > 0.0 => Some(HomeZone {
+            name: zone_name.trim().to_string(),
+            latitude,
+            longitude,
+            radius,
+        }),
+        _ => None,
+    };
+    let onaddzone = {
+        let new_zone = new_zone.clone();
+        let zone_name = zone_name.clone();
+        Callback::from(move |_| {
+            if let Some(zone) = new_zone.clone() {
+                update_home_zones(|zones| zones.push(zone));
+                zone_name.set(String::new());
+            }
+        })
+    };
+
+    let in_zones_count = props
+        .ids
+        .iter()
+        .filter_map(|id| cache.get(id)?.location)
+        .filter(|(latitude, longitude, _)| home_zones.iter().any(|zone| zone.contains(*latitude, *longitude)))
+        .count();
+    let mut count_args = FluentArgs::new();
+    count_args.set("count", props.ids.len());
+    let mut zones_args = FluentArgs::new();
+    zones_args.set("count", in_zones_count);
+
+    html! {
+        <form class="metadata-editor export" {onsubmit}>
+            <h3>{t.tra("export-title", &count_args)}</h3>
+            <div class="destination">
+                <span title={(*destination).clone().unwrap_or_default()}>
+                    {(*destination).clone().unwrap_or_else(|| t.tr("export-no-destination"))}
+                </span>
+                <button type="button" onclick={onpickdestination}>{t.tr("export-choose-destination")}</button>
+            </div>
+            <h4>{t.tr("export-strip")}</h4>
+            {strip_checkbox("export-strip-all", |strip| strip.all, |strip, checked| strip.all = checked)}
+            {strip_checkbox("export-strip-gps", |strip| strip.gps, |strip, checked| strip.gps = checked)}
+            {strip_checkbox("export-strip-serials", |strip| strip.serials, |strip, checked| strip.serials = checked)}
+            {strip_checkbox("export-strip-uid", |strip| strip.uid, |strip, checked| strip.uid = checked)}
+
+            <h4>{t.tr("export-home-zones")}</h4>
+            <p class="hint">{t.tra("export-home-zones-hint", &zones_args)}</p>
+            <ul class="home-zones">
+                {
+                    home_zones.iter().enumerate().map(|(i, zone)| {
+                        let onremove = Callback::from(move |_| {
+                            update_home_zones(|zones| {
+                                zones.remove(i);
+                            });
+                        });
+                        let name = if zone.name.is_empty() { format!("{:.4}, {:.4}", zone.latitude, zone.longitude) } else { zone.name.clone() };
+                        html! {
+                            <li>
+                                <span>{name}</span>
+                                <span class="radius">{format!("{} m", zone.radius)}</span>
+                                <button type="button" onclick={onremove}>{"×"}</button>
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+            <div class="new-home-zone">
+                <input type="text" placeholder={t.tr("export-home-zone-name")} value={(*zone_name).clone()} oninput={oninput_text(zone_name.clone())}/>
+                <input type="text" placeholder={t.tr("metadata-latitude")} value={(*zone_latitude).clone()} oninput={oninput_text(zone_latitude.clone())}/>
+                <input type="text" placeholder={t.tr("metadata-longitude")} value={(*zone_longitude).clone()} oninput={oninput_text(zone_longitude.clone())}/>
+                <input type="number" min="1" step="1" title={t.tr("export-home-zone-radius")} value={(*zone_radius).clone()} oninput={oninput_text(zone_radius.clone())}/>
+                <button type="button" disabled={new_zone.is_none()} onclick={onaddzone}>{t.tr("export-add-home-zone")}</button>
+            </div>
+            {
+                if let Some(count) = *exported {
+                    let mut args = FluentArgs::new();
+                    args.set("count", count);
+                    html! { <p class="success">{t.tra("export-done", &args)}</p> }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if let Some(error) = &*error {
+                    html! { <p class="error">{error}</p> }
+                } else {
+                    html! {}
+                }
+            }
+            <div class="buttons">
+                <button type="button" onclick={oncancel}>{t.tr("export-close")}</button>
+                <button type="submit" disabled={*exporting || destination.is_none()}>{t.tr("export-apply")}</button>
+            </div>
+        </form>
+    }
+}
+
+// The home zones are applied by the backend, which only gets the gallery settings on close otherwise.
+fn update_home_zones(update: impl FnOnce(&mut Vec<HomeZone>)) {
+    let dispatch = Dispatch::<GallerySettings>::global();
+    dispatch.reduce_mut(|settings| update(&mut settings.home_zones));
+    cmd_arg(
+        "set_gallery_settings",
+        &GallerySettingsContainer {
+            settings: (*dispatch.get()).clone(),
+        },
+    );
+}
@@ -0,0 +1,297 @@
+use fluent::FluentArgs;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::{function_component, html, use_state, Callback, Event, Html, InputEvent, Properties, SubmitEvent, TargetCast};
+use yewdux::prelude::{use_selector, use_store};
+
+use pm_common::metadata::{Geotag, GeotagPreview, MetadataEditError};
+
+use crate::mainpane::mainpane::CacheContext;
+use crate::utils::translator::Translator;
+use crate::utils::utils::{cmd_async_get, cmd_async_result};
+
+#[derive(Serialize, Deserialize)]
+struct GeotagArgs {
+    ids: Vec<String>,
+    geotag: Geotag,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GeotagToolProps {
+    pub ids: Vec<String>,
+    pub on_close: Callback<()>,
+}
+
+/// Locates the selected pictures from GPX, KML or GeoJSON tracks, by matching their capture time with the time of the track points.
+/// The locations are previewed before being written.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn GeotagTool(props: &GeotagToolProps) -> Html {
+    let (t, _) = use_store::<Translator>();
+    let cache = use_selector(|cache: &CacheContext| cache.datas_cache.clone());
+    let tracks = use_state(Vec::<String>::new);
+    let clock_offset = use_state(|| [String::new(), String::new(), String::new()]); // Hours, minutes, seconds
+    let timezone = use_state(get_local_timezone);
+    let max_gap = use_state(|| "5".to_string()); // Minutes
+    let overwrite = use_state(|| false);
+    let preview = use_state(|| None::<Vec<GeotagPreview>>);
+    let error = use_state(|| None::<String>);
+    let saving = use_state(|| false);
+
+    // Any change of the parameters invalidates the preview
+    let on_change = {
+        let preview = preview.clone();
+        let error = error.clone();
+        move || {
+            preview.set(None);
+            error.set(None);
+        }
+    };
+
+    let get_geotag = {
+        let t = t.clone();
+        let tracks = tracks.clone();
+        let clock_offset = clock_offset.clone();
+        let timezone = timezone.clone();
+        let max_gap = max_gap.clone();
+        let overwrite = overwrite.clone();
+        move || -> Result<Geotag, String> {
+            let parse = |value: &str| -> Result<i64, String> {
+                let value = value.trim();
+                if value.is_empty() {
+                    Ok(0)
+                } else {
+                    value.parse::<i64>().map_err(|_| t.tr("metadata-error-invalid-number"))
+                }
+            };
+            let mut seconds = 0;
+            for (value, unit) in clock_offset.iter().zip([3600, 60, 1]) {
+                seconds += parse(value)? * unit;
+            }
+            let timezone = timezone.trim();
+            Ok(Geotag {
+                tracks: (*tracks).clone(),
+                clock_offset: seconds,
+                timezone: if timezone.is_empty() { None } else { Some(timezone.to_string()) },
+                max_gap: parse(&max_gap)?.max(0) * 60,
+                overwrite: *overwrite,
+            })
+        }
+    };
+
+    let onaddtracks = {
+        let tracks = tracks.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |_| {
+            let (tracks, on_change) = (tracks.clone(), on_change.clone());
+            spawn_local(async move {
+                let mut new_tracks = (*tracks).clone();
+                for path in cmd_async_get::<Vec<String>>("pick_track_files").await {
+                    if !new_tracks.contains(&path) {
+                        new_tracks.push(path);
+                    }
+                }
+                tracks.set(new_tracks);
+                on_change();
+            });
+        })
+    };
+
+    let onpreview = {
+        let t = t.clone();
+        let ids = props.ids.clone();
+        let preview = preview.clone();
+        let error = error.clone();
+        let get_geotag = get_geotag.clone();
+        Callback::from(move |_| {
+            let geotag = match get_geotag() {
+                Ok(geotag) => geotag,
+                Err(e) => return error.set(Some(e)),
+            };
+            let (t, ids, preview, error) = (t.clone(), ids.clone(), preview.clone(), error.clone());
+            spawn_local(async move {
+                match cmd_async_result::<_, Vec<GeotagPreview>, MetadataEditError>("preview_geotag", &GeotagArgs { ids, geotag }).await {
+                    Ok(result) => preview.set(Some(result)),
+                    Err(e) => error.set(Some(t.tr(e.translation_key()))),
+                }
+            });
+        })
+    };
+
+    let onsubmit = {
+        let t = t.clone();
+        let ids = props.ids.clone();
+        let on_close = props.on_close.clone();
+        let error = error.clone();
+        let saving = saving.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let geotag = match get_geotag() {
+                Ok(geotag) => geotag,
+                Err(e) => return error.set(Some(e)),
+            };
+            let (t, ids, on_close, error, saving) = (t.clone(), ids.clone(), on_close.clone(), error.clone(), saving.clone());
+            saving.set(true);
+            spawn_local(async move {
+                let result = cmd_async_result::<_, (), MetadataEditError>("geotag_pictures", &GeotagArgs { ids, geotag }).await;
+                saving.set(false);
+                match result {
+                    Ok(()) => on_close.emit(()),
+                    Err(e) => {
+                        let mut args = FluentArgs::new();
+                        if let MetadataEditError::WriteFailed(paths) = &e {
+                            args.set("count", paths.len());
+                        }
+                        error.set(Some(t.tra(e.translation_key(), &args)));
+                    }
+                }
+            });
+        })
+    };
+    let oncancel = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+
+    let ontimezone = {
+        let timezone = timezone.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            timezone.set(e.target_unchecked_into::<HtmlInputElement>().value());
+            on_change();
+        })
+    };
+    let onmaxgap = {
+        let max_gap = max_gap.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            max_gap.set(e.target_unchecked_into::<HtmlInputElement>().value());
+            on_change();
+        })
+    };
+    let onoverwrite = {
+        let overwrite = overwrite.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            overwrite.set(e.target_unchecked_into::<HtmlInputElement>().checked());
+            on_change();
+        })
+    };
+
+    let located_count = (*preview)
+        .as_ref()
+        .map_or(0, |preview| preview.iter().filter(|p| p.location.is_some()).count());
+    html! {
+        <form class="metadata-editor geotag" {onsubmit}>
+            <h3>{t.tr("geotag-title")}</h3>
+            <ul class="geotag-tracks">
+                {
+                    tracks.iter().enumerate().map(|(i, path)| {
+                        let onremove = {
+                            let tracks = tracks.clone();
+                            let on_change = on_change.clone();
+                            Callback::from(move |_| {
+                                let mut new_tracks = (*tracks).clone();
+                                new_tracks.remove(i);
+                                tracks.set(new_tracks);
+                                on_change();
+                            })
+                        };
+                        let name = path.rsplit(&['/', '\\'][..]).next().unwrap_or_default().to_string();
+                        html! {
+                            <li key={path.clone()} title={path.clone()}>
+                                <span>{name}</span>
+                                <button type="button" onclick={onremove}>{"×"}</button>
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ul>
+            <button type="button" onclick={onaddtracks}>{t.tr("geotag-add-tracks")}</button>
+            <p class="hint">{t.tr("geotag-clock-offset-hint")}</p>
+            {
+                ["geotag-clock-offset-hours", "geotag-clock-offset-minutes", "geotag-clock-offset-seconds"].iter().enumerate().map(|(i, key)| {
+                    let oninput = {
+                        let clock_offset = clock_offset.clone();
+                        let on_change = on_change.clone();
+                        Callback::from(move |e: InputEvent| {
+                            let mut new_offset = (*clock_offset).clone();
+                            new_offset[i] = e.target_unchecked_into::<HtmlInputElement>().value();
+                            clock_offset.set(new_offset);
+                            on_change();
+                        })
+                    };
+                    html! {
+                        <label>
+                            <span>{t.tr(key)}</span>
+                            <input type="number" step="1" placeholder="0" value={clock_offset[i].clone()} {oninput}/>
+                        </label>
+                    }
+                }).collect::<Html>()
+            }
+            <label>
+                <span>{t.tr("geotag-timezone")}</span>
+                <input type="text" placeholder="+02:00" value={(*timezone).clone()} oninput={ontimezone}/>
+            </label>
+            <label>
+                <span>{t.tr("geotag-max-gap")}</span>
+                <input type="number" step="1" min="0" value={(*max_gap).clone()} oninput={onmaxgap}/>
+            </label>
+            <label class="checkbox">
+                <input type="checkbox" checked={*overwrite} onchange={onoverwrite}/>
+                <span>{t.tr("geotag-overwrite")}</span>
+            </label>
+            {
+                if let Some(preview) = &*preview {
+                    let mut args = FluentArgs::new();
+                    args.set("located", located_count);
+                    args.set("count", preview.len());
+                    html! {
+                        <>
+                            <p>{t.tra("geotag-preview-count", &args)}</p>
+                            <ul class="date-shift-preview">
+                                {
+                                    preview.iter().map(|picture_location| {
+                                        let name = cache.get(&picture_location.id).and_then(|picture| picture.path.rsplit('/').next()).unwrap_or_default().to_string();
+                                        let location = match picture_location.location {
+                                            Some((lat, lon, _)) => format!("{:.5}, {:.5}", lat, lon),
+                                            None => t.tr("geotag-no-match"),
+                                        };
+                                        html! {
+                                            <li key={picture_location.id.clone()}>
+                                                <span>{name}</span>
+                                                {" → "}
+                                                <span>{location}</span>
+                                            </li>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </ul>
+                        </>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {
+                if let Some(error) = &*error {
+                    html! { <p class="error">{error}</p> }
+                } else {
+                    html! {}
+                }
+            }
+            <div class="buttons">
+                <button type="button" onclick={oncancel}>{t.tr("metadata-cancel")}</button>
+                <button type="button" disabled={tracks.is_empty()} onclick={onpreview}>{t.tr("geotag-preview")}</button>
+                <button type="submit" disabled={*saving || located_count == 0}>{t.tr("geotag-apply")}</button>
+            </div>
+        </form>
+    }
+}
+
+// Current timezone of the computer, formatted as ±HH:MM, used for the pictures without recorded timezone.
+fn get_local_timezone() -> String {
+    let offset = -js_sys::Date::new_0().get_timezone_offset() as i32; // In minutes
+    format!("{}{:02}:{:02}", if offset < 0 { '-' } else { '+' }, offset.abs() / 60, offset.abs() % 60)
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use fluent::FluentArgs;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::{function_component, html, use_state, Callback, Html, InputEvent, Properties, SubmitEvent, TargetCast};
+use yewdux::prelude::{use_selector, use_store};
+
+use pm_common::gallery_cache::PictureCache;
+use pm_common::metadata::{MetadataEdit, MetadataEditError};
+use pm_common::ratings::PictureRating;
+
+use crate::mainpane::mainpane::CacheContext;
+use crate::utils::translator::Translator;
+use crate::utils::utils::cmd_async_result;
+
+#[derive(Serialize, Deserialize)]
+struct EditPicturesMetadataArgs {
+    ids: Vec<String>,
+    edit: MetadataEdit,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FieldKind {
+    Text,
+    Number,
+    Date,
+}
+
+// Editable fields: id, translation key and kind.
+const FIELDS: [(&str, &str, FieldKind); 11] = [
+    ("title", "metadata-title", FieldKind::Text),
+    ("description", "metadata-description", FieldKind::Text),
+    ("keywords", "metadata-keywords", FieldKind::Text),
+    ("rating", "metadata-rating", FieldKind::Number),
+    ("date", "metadata-date", FieldKind::Date),
+    ("latitude", "metadata-latitude", FieldKind::Number),
+    ("longitude", "metadata-longitude", FieldKind::Number),
+    ("altitude", "metadata-altitude", FieldKind::Number),
+    ("camera_make", "metadata-camera-make", FieldKind::Text),
+    ("camera_model", "metadata-camera-model", FieldKind::Text),
+    ("copyright", "metadata-copyright", FieldKind::Text),
+];
+
+// The rating is the one of the gallery, which might not be written to the metadata yet.
+fn get_field_value(id: &str, picture: &PictureCache, rating: Option<&PictureRating>) -> Option<String> {
+    match id {
+        "title" => picture.title.clone(),
+        "description" => picture.description.clone(),
+        "keywords" => Some(picture.keywords.join(", ")),
+        "rating" => Some(
+            rating
+                .map(|rating| rating.get_xmp_rating())
+                .filter(|rating| *rating != 0)
+                .map(|rating| rating.to_string())
+                .unwrap_or_default(),
+        ),
+        "date" => picture.date.as_deref().map(exif_date_to_input),
+        "latitude" => picture.location.map(|location| location.0.to_string()),
+        "longitude" => picture.location.map(|location| location.1.to_string()),
+        "altitude" => picture.location.map(|location| location.2.to_string()),
+        "camera_make" => picture.camera_make.clone(),
+        "camera_model" => picture.camera.clone(),
+        "copyright" => picture.copyright.clone(),
+        _ => None,
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MetadataEditorProps {
+    pub ids: Vec<String>,
+    pub on_close: Callback<()>,
+}
+
+/// Form editing the metadata of the selected pictures. Only the modified fields are written,
+/// fields with different values among the pictures are left empty.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn MetadataEditor(props: &MetadataEditorProps) -> Html {
+    let (t, _) = use_store::<Translator>();
+    let cache = use_selector(|cache: &CacheContext| cache.datas_cache.clone());
+    let ratings = use_selector(|cache: &CacheContext| cache.ratings.clone());
+    let edited = use_state(HashMap::<&'static str, String>::new);
+    let error = use_state(|| None::<String>);
+    let saving = use_state(|| false);
+
+    let pictures: Vec<(&PictureCache, Option<&PictureRating>)> = props.ids.iter().filter_map(|id| Some((cache.get(id)?, ratings.get(id)))).collect();
+    // Value shared by all the pictures, None if they differ
+    let get_common_value = |id: &str| {
+        let first = pictures.first().and_then(|(picture, rating)| get_field_value(id, picture, *rating));
+        pictures
+            .iter()
+            .all(|(picture, rating)| get_field_value(id, picture, *rating) == first)
+            .then(|| first.unwrap_or_default())
+    };
+    let values: HashMap<&'static str, Option<String>> = FIELDS
+        .iter()
+        .map(|(id, _, _)| (*id, edited.get(id).cloned().or_else(|| get_common_value(id))))
+        .collect();
+
+    let onsubmit = {
+        let t = t.clone();
+        let ids = props.ids.clone();
+        let on_close = props.on_close.clone();
+        let edited = edited.clone();
+        let error = error.clone();
+        let saving = saving.clone();
+        let values = values.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let edit = match get_edit(&edited, &values) {
+                Ok(edit) => edit,
+                Err(key) => {
+                    error.set(Some(t.tr(key)));
+                    return;
+                }
+            };
+            let (t, ids, on_close, error, saving) = (t.clone(), ids.clone(), on_close.clone(), error.clone(), saving.clone());
+            saving.set(true);
+            spawn_local(async move {
+                let result = cmd_async_result::<_, (), MetadataEditError>("edit_pictures_metadata", &EditPicturesMetadataArgs { ids, edit }).await;
+                saving.set(false);
+                match result {
+                    Ok(()) => on_close.emit(()),
+                    Err(e) => {
+                        let mut args = FluentArgs::new();
+                        if let MetadataEditError::WriteFailed(paths) = &e {
+                            args.set("count", paths.len());
+                        }
+                        error.set(Some(t.tra(e.translation_key(), &args)));
+                    }
+                }
+            });
+        })
+    };
+    let oncancel = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+
+    html! {
+        <form class="metadata-editor" {onsubmit}>
+            <h3>{t.tr("metadata-editor-title")}</h3>
+            {
+                FIELDS.iter().map(|(id, key, kind)| {
+                    let id = *id;
+                    let value = values.get(id).cloned().flatten();
+                    let placeholder = if value.is_none() { t.tr("metadata-multiple-values") } else { String::new() };
+                    let oninput = {
+                        let edited = edited.clone();
+                        Callback::from(move |e: InputEvent| {
+                            let mut new_edited = (*edited).clone();
+                            new_edited.insert(id, e.target_unchecked_into::<HtmlInputElement>().value());
+                            edited.set(new_edited);
+                        })
+                    };
+                    let (input_type, step) = match kind {
+                        FieldKind::Text => ("text", None),
+                        FieldKind::Number => ("number", Some("any")),
+                        FieldKind::Date => ("datetime-local", Some("1")),
+                    };
+                    html! {
+                        <label class={if edited.contains_key(id) { "edited" } else { "" }}>
+                            <span>{t.tr(key)}</span>
+                            <input type={input_type} {step} {placeholder} value={value.unwrap_or_default()} {oninput}/>
+                        </label>
+                    }
+                }).collect::<Html>()
+            }
+            {
+                if let Some(error) = &*error {
+                    html! { <p class="error">{error}</p> }
+                } else {
+                    html! {}
+                }
+            }
+            <div class="buttons">
+                <button type="button" onclick={oncancel}>{t.tr("metadata-cancel")}</button>
+                <button type="submit" disabled={*saving || edited.is_empty()}>{t.tr("metadata-save")}</button>
+            </div>
+        </form>
+    }
+}
+
+// Builds the edit from the modified fields. Returns the translation key of the error if a number is invalid.
+fn get_edit(edited: &HashMap<&'static str, String>, values: &HashMap<&'static str, Option<String>>) -> Result<MetadataEdit, &'static str> {
+    let mut edit = MetadataEdit {
+        date: edited.get("date").map(|date| input_date_to_exif(date)),
+        camera_make: edited.get("camera_make").cloned(),
+        camera_model: edited.get("camera_model").cloned(),
+        title: edited.get("title").cloned(),
+        description: edited.get("description").cloned(),
+        copyright: edited.get("copyright").cloned(),
+        keywords: edited.get("keywords").map(|keywords| {
+            keywords
+                .split(',')
+                .map(|keyword| keyword.trim().to_string())
+                .filter(|keyword| !keyword.is_empty())
+                .collect()
+        }),
+        ..Default::default()
+    };
+    if let Some(rating) = edited.get("rating").map(|rating| rating.trim()) {
+        edit.rating = Some(if rating.is_empty() {
+            0
+        } else {
+            rating.parse::<i32>().map_err(|_| "metadata-error-invalid-number")?
+        });
+    }
+
+    // The location is written as a whole, emptying both coordinates removes it.
+    if ["latitude", "longitude", "altitude"].iter().any(|id| edited.contains_key(id)) {
+        let get = |id: &str| values.get(id).cloned().flatten().unwrap_or_default().trim().to_string();
+        let (latitude, longitude, altitude) = (get("latitude"), get("longitude"), get("altitude"));
+        if latitude.is_empty() && longitude.is_empty() {
+            edit.remove_location = true;
+        } else {
+            let parse = |value: &str| value.parse::<f64>().map_err(|_| "metadata-error-invalid-number");
+            let altitude = if altitude.is_empty() { None } else { Some(parse(&altitude)?) };
+            edit.location = Some((parse(&latitude)?, parse(&longitude)?, altitude));
+        }
+    }
+    Ok(edit)
+}
+
+// EXIF dates (`YYYY:MM:DD HH:MM:SS`) are edited with datetime-local inputs (`YYYY-MM-DDTHH:MM:SS`).
+pub fn exif_date_to_input(date: &str) -> String {
+    match date.split_once(' ') {
+        Some((date, time)) => format!("{}T{}", date.replace(':', "-"), time),
+        None => date.replace(':', "-"),
+    }
+}
+pub fn input_date_to_exif(date: &str) -> String {
+    let Some((date, time)) = date.split_once('T') else {
+        return date.to_string(); // Empty when cleared
+    };
+    // Seconds are omitted by the input when they are zero
+    let time = if time.len() == 5 { format!("{}:00", time) } else { time.to_string() };
+    format!("{} {}", date.replace('-', ":"), time)
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::{classes, function_component, html, use_effect_with, use_state, Callback, Html, InputEvent, Properties, TargetCast};
+use yew_icons::{Icon, IconId};
+use yewdux::prelude::{use_selector_with_deps, use_store};
+
+use pm_common::metadata::{MetadataGroup, MetadataTag};
+
+use crate::mainpane::mainpane::CacheContext;
+use crate::utils::translator::Translator;
+use crate::utils::utils::cmd_async;
+
+#[derive(Serialize, Deserialize)]
+struct GetPictureMetadataArgs {
+    id: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MetadataInspectorProps {
+    pub id: String,
+}
+
+/// Lists all the EXIF, IPTC and XMP tags of a picture, grouped by namespace.
+/// Groups can be collapsed, tags can be searched by key, name or value, and values copied to the clipboard.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn MetadataInspector(props: &MetadataInspectorProps) -> Html {
+    let (t, _) = use_store::<Translator>();
+    // The metadata is read again when the picture cache changes, after an edit
+    let picture = use_selector_with_deps(|cache: &CacheContext, id: &String| cache.datas_cache.get(id).cloned(), props.id.clone());
+    let groups = use_state(|| None::<Option<Vec<MetadataGroup>>>); // None while loading
+    let search = use_state(String::new);
+    let collapsed = use_state(HashSet::<String>::new);
+
+    {
+        let groups = groups.clone();
+        use_effect_with((props.id.clone(), picture), move |(id, _)| {
+            let id = id.clone();
+            spawn_local(async move {
+                groups.set(Some(
+                    cmd_async::<_, Option<Vec<MetadataGroup>>>("get_picture_metadata", &GetPictureMetadataArgs { id }).await,
+                ));
+            });
+        });
+    }
+
+    let oninput = {
+        let search = search.clone();
+        Callback::from(move |e: InputEvent| search.set(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+
+    let query = search.trim().to_lowercase();
+    let content = match &*groups {
+        None => html! {},
+        Some(None) => html! { <p class="hint">{t.tr("metadata-inspector-no-metadata")}</p> },
+        Some(Some(groups)) => {
+            let groups = groups
+                .iter()
+                .filter_map(|group| {
+                    let tags: Vec<&MetadataTag> = group.tags.iter().filter(|tag| query.is_empty() || tag_matches(tag, &query)).collect();
+                    (!tags.is_empty()).then_some((group, tags))
+                })
+                .collect::<Vec<_>>();
+            if groups.is_empty() {
+                html! { <p class="hint">{t.tr("metadata-inspector-no-tags")}</p> }
+            } else {
+                groups
+                    .into_iter()
+                    .map(|(group, tags)| {
+                        // Matching groups are always expanded while searching
+                        let is_collapsed = query.is_empty() && collapsed.contains(&group.name);
+                        let ontoggle = {
+                            let collapsed = collapsed.clone();
+                            let name = group.name.clone();
+                            Callback::from(move |_| {
+                                let mut new_collapsed = (*collapsed).clone();
+                                if !new_collapsed.remove(&name) {
+                                    new_collapsed.insert(name.clone());
+                                }
+                                collapsed.set(new_collapsed);
+                            })
+                        };
+                        html! {
+                            <div class="group" key={group.name.clone()}>
+                                <div class={classes!("group-header", if is_collapsed { None } else { Some("opened") })} onclick={ontoggle}>
+                                    <Icon icon_id={IconId::FontAwesomeSolidAngleRight}/>
+                                    <p>{&group.name}</p>
+                                    <span class="count">{tags.len()}</span>
+                                </div>
+                                {
+                                    if is_collapsed {
+                                        html! {}
+                                    } else {
+                                        html! {
+                                            <table>
+                                                { tags.into_iter().map(|tag| view_tag(tag, &t)).collect::<Html>() }
+                                            </table>
+                                        }
+                                    }
+                                }
+                            </div>
+                        }
+                    })
+                    .collect::<Html>()
+            }
+        }
+    };
+
+    html! {
+        <div class="metadata-inspector">
+            <h3>{t.tr("metadata-inspector-title")}</h3>
+            <input type="search" placeholder={t.tr("metadata-inspector-search")} value={(*search).clone()} {oninput}/>
+            {content}
+        </div>
+    }
+}
+
+// Longer values, like maker notes or embedded thumbnails, are truncated in the view but copied entirely.
+const MAX_DISPLAYED_VALUE_LENGTH: usize = 512;
+
+fn view_tag(tag: &MetadataTag, t: &Translator) -> Html {
+    html! {
+        <tr key={tag.key.clone()}>
+            <th title={tag.key.clone()}>{&tag.label}</th>
+            <td>
+                <div class="value">
+                    <span>{truncate_value(&tag.interpreted)}</span>
+                    {copy_button(tag.interpreted.clone(), t)}
+                </div>
+                {
+                    // The raw value is only shown when exiv2 interpreted it
+                    if tag.value != tag.interpreted {
+                        html! {
+                            <div class="value raw">
+                                <span>{truncate_value(&tag.value)}</span>
+                                {copy_button(tag.value.clone(), t)}
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </td>
+        </tr>
+    }
+}
+
+fn truncate_value(value: &str) -> String {
+    match value.char_indices().nth(MAX_DISPLAYED_VALUE_LENGTH) {
+        Some((index, _)) => format!("{}…", &value[..index]),
+        None => value.to_string(),
+    }
+}
+
+fn copy_button(value: String, t: &Translator) -> Html {
+    let onclick = Callback::from(move |_| {
+        let value = value.clone();
+        spawn_local(async move {
+            if let Err(e) = tauri_sys::clipboard::write_text(&value).await {
+                warn!("Unable to copy the value to the clipboard: {:?}", e);
+            }
+        });
+    });
+    html! {
+        <button class="copy" title={t.tr("metadata-inspector-copy")} {onclick}>
+            <Icon icon_id={IconId::LucideCopy}/>
+        </button>
+    }
+}
+
+fn tag_matches(tag: &MetadataTag, query: &str) -> bool {
+    [&tag.key, &tag.label, &tag.value, &tag.interpreted]
+        .iter()
+        .any(|text| text.to_lowercase().contains(query))
+}
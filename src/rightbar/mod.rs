@@ -1,2 +1,8 @@
+pub mod date_shift;
+pub mod export;
+pub mod geotag;
+pub mod metadata_editor;
+pub mod metadata_inspector;
+pub mod picture_info;
 pub mod picture_preview;
 pub mod rightbar;
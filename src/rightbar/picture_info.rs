@@ -0,0 +1,168 @@
+use yew::{function_component, html, Html, Properties};
+use yewdux::prelude::{use_selector, use_selector_with_deps, use_store};
+
+use pm_common::gallery_cache::{DateSource, PictureCache};
+use pm_common::ratings::PictureRating;
+
+use crate::mainpane::mainpane::CacheContext;
+use crate::utils::translator::Translator;
+
+#[derive(Properties, PartialEq)]
+pub struct PictureInfoProps {
+    pub id: String,
+}
+
+/// Technical metadata of a picture, missing values are not listed.
+#[allow(non_snake_case)]
+#[function_component]
+pub fn PictureInfo(props: &PictureInfoProps) -> Html {
+    let (t, _) = use_store::<Translator>();
+    let cache = use_selector(|cache: &CacheContext| cache.datas_cache.clone());
+    let rating = use_selector_with_deps(|cache: &CacheContext, id: &String| cache.ratings.get(id).copied(), props.id.clone());
+    let Some(picture) = cache.get(&props.id) else {
+        return html! {};
+    };
+
+    let rows = get_info_rows(picture, *rating, &t);
+    html! {
+        <table class="picture-info">
+            {
+                rows.into_iter().map(|(key, value)| html! {
+                    <tr>
+                        <th>{t.tr(key)}</th>
+                        <td>{value}</td>
+                    </tr>
+                }).collect::<Html>()
+            }
+        </table>
+    }
+}
+
+// Translation key of the label and formatted value of each known information.
+// The rating is the one of the gallery, which might not be written to the metadata.
+fn get_info_rows(picture: &PictureCache, rating: Option<PictureRating>, t: &Translator) -> Vec<(&'static str, String)> {
+    let camera = match (&picture.camera_make, &picture.camera) {
+        // Models often already start with the make
+        (Some(make), Some(model)) if model.starts_with(make.as_str()) => Some(model.clone()),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.clone().or_else(|| model.clone()),
+    };
+    let focal_length = picture.focal_length.map(|focal_length| match picture.focal_length_35mm {
+        Some(focal_length_35mm) => format!("{} mm ({} mm 35mm eq.)", focal_length, focal_length_35mm),
+        None => format!("{} mm", focal_length),
+    });
+    let date_source = picture.capture_date.map(|date| {
+        t.tr(match date.source {
+            DateSource::DateTimeOriginal => "picture-info-date-source-original",
+            DateSource::CreateDate => "picture-info-date-source-create-date",
+            DateSource::XmpDateCreated => "picture-info-date-source-xmp",
+            DateSource::FileName => "picture-info-date-source-file-name",
+            DateSource::FileModified => "picture-info-date-source-file-modified",
+        })
+    });
+    let place = [&picture.city, &picture.region, &picture.country]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    let flash = picture.flash.map(|flash| {
+        t.tr(if flash & 1 == 1 {
+            "picture-info-flash-fired"
+        } else {
+            "picture-info-flash-not-fired"
+        })
+    });
+    let metering_mode = picture.metering_mode.map(|mode| {
+        t.tr(match mode {
+            1 => "picture-info-metering-average",
+            2 => "picture-info-metering-center-weighted",
+            3 => "picture-info-metering-spot",
+            4 => "picture-info-metering-multi-spot",
+            5 => "picture-info-metering-pattern",
+            6 => "picture-info-metering-partial",
+            _ => "picture-info-unknown",
+        })
+    });
+    let white_balance = picture.white_balance.map(|white_balance| {
+        t.tr(match white_balance {
+            0 => "picture-info-white-balance-auto",
+            1 => "picture-info-white-balance-manual",
+            _ => "picture-info-unknown",
+        })
+    });
+
+    [
+        ("picture-info-title", picture.title.clone()),
+        ("picture-info-description", picture.description.clone()),
+        (
+            "picture-info-keywords",
+            Some(picture.keywords.join(", ")).filter(|keywords| !keywords.is_empty()),
+        ),
+        (
+            "picture-info-rating",
+            rating
+                .map(|rating| rating.get_xmp_rating())
+                .filter(|rating| *rating != 0)
+                .map(|rating| format_rating(rating, t)),
+        ),
+        ("picture-info-file", picture.path.rsplit('/').next().map(|name| name.to_string())),
+        ("picture-info-format", picture.file_format.clone()),
+        ("picture-info-file-size", Some(format_file_size(picture.file_size))),
+        (
+            "picture-info-dimensions",
+            Some(format!("{} × {}", picture.dimensions.0, picture.dimensions.1)),
+        ),
+        ("picture-info-date", picture.capture_date.map(|date| date.format())),
+        ("picture-info-date-source", date_source),
+        ("picture-info-camera", camera),
+        ("picture-info-lens", picture.lens.clone()),
+        ("picture-info-focal-length", focal_length),
+        ("picture-info-exposure-time", picture.exposure_time.map(format_exposure_time)),
+        ("picture-info-f-number", picture.f_number.map(|f_number| format!("f/{}", f_number))),
+        ("picture-info-iso", picture.iso_speed.map(|iso| iso.to_string())),
+        ("picture-info-exposure-bias", picture.exposure_bias.map(|bias| format!("{:+.1} EV", bias))),
+        ("picture-info-flash", flash),
+        ("picture-info-metering", metering_mode),
+        ("picture-info-white-balance", white_balance),
+        ("picture-info-software", picture.software.clone()),
+        ("picture-info-place", Some(place).filter(|place| !place.is_empty())),
+        (
+            "picture-info-location",
+            picture.location.map(|(lat, lon, _)| format!("{:.5}, {:.5}", lat, lon)),
+        ),
+        (
+            "picture-info-gps-direction",
+            picture.gps_direction.map(|direction| format!("{:.0}°", direction)),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value?)))
+    .collect()
+}
+
+fn format_exposure_time((numer, denom): (u32, u32)) -> String {
+    if denom == 0 {
+        String::new()
+    } else if numer < denom && numer != 0 {
+        format!("1/{} s", (denom as f64 / numer as f64).round())
+    } else {
+        format!("{} s", numer as f64 / denom as f64)
+    }
+}
+
+fn format_file_size(size: u64) -> String {
+    match size {
+        0..=1023 => format!("{} B", size),
+        1024..=1048575 => format!("{:.1} KB", size as f64 / 1024.0),
+        _ => format!("{:.1} MB", size as f64 / 1048576.0),
+    }
+}
+
+fn format_rating(rating: i32, t: &Translator) -> String {
+    if rating < 0 {
+        t.tr("picture-info-rating-rejected")
+    } else {
+        "★".repeat(rating as usize) + &"☆".repeat(5 - rating.min(5) as usize)
+    }
+}
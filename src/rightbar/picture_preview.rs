@@ -1,9 +1,14 @@
 use log::warn;
+use web_sys::window;
 use yew::suspense::Suspense;
 use yew::{function_component, html, suspense::use_future_with, use_context, HtmlResult, Properties};
 use yew::{use_node_ref, Html};
+use yew_hooks::use_size;
+
+use pm_common::thumbnails::ThumbnailPriority;
 
 use crate::mainpane::full_picture::GetImageArgs;
+use crate::utils::thumbnails::{use_placeholder_style, use_preview, use_thumbnail};
 use crate::{app::StaticContext, utils::utils::cmd_async};
 
 #[derive(Properties, PartialEq)]
@@ -72,24 +77,27 @@ pub struct PictureThumbPreviewImageProps {
 }
 #[allow(non_snake_case)]
 #[function_component]
-fn PictureThumbPreviewImage(props: &PicturePreviewImageProps) -> HtmlResult {
+fn PictureThumbPreviewImage(props: &PicturePreviewImageProps) -> Html {
     let static_ctx = use_context::<StaticContext>().unwrap();
 
-    let has_thumb = use_future_with(props.id.clone(), |id| async move {
-        cmd_async::<GetImageArgs, bool>("gen_image_thumbnail", &GetImageArgs { id: id.to_string() }).await
-    })?;
+    let has_thumb = use_thumbnail(props.id.clone(), ThumbnailPriority::Visible);
+    let placeholder = use_placeholder_style(props.id.clone());
 
-    if !*has_thumb {
-        warn!("No thumb for {}", props.id);
-        return Ok(html! {});
+    match has_thumb {
+        None => html! { <div class="thumb" style={placeholder}></div> },
+        Some(false) => {
+            warn!("No thumb for {}", props.id);
+            html! {}
+        }
+        Some(true) => html! {
+            <div class="thumb" style={format!("/*aspect-ratio: {} / {};*/ {}", props.width, props.height, placeholder)}>
+                <div class="thumb-image"
+                    style={format!("background-image: url({}/get-thumbnail?id={}&window={});",
+                    static_ctx.protocol, props.id, static_ctx.window_label)}>
+                </div>
+            </div>
+        },
     }
-
-    Ok(html! {
-        <div class="thumb"
-            style={format!("background-image: url({}/get-thumbnail?id={}&window={}); /*aspect-ratio: {} / {};*/",
-            static_ctx.protocol, props.id, static_ctx.window_label, props.width, props.height)}>
-        </div>
-    })
 }
 
 #[derive(Properties, PartialEq)]
@@ -102,10 +110,28 @@ pub struct PicturePreviewImageProps {
 #[function_component]
 fn PicturePreviewImage(props: &PicturePreviewImageProps) -> Html {
     let static_ctx = use_context::<StaticContext>().unwrap();
+
+    let ref_pic = use_node_ref();
+    let (width, height) = use_size(ref_pic.clone());
+    let pixel_ratio = window().map_or(1.0, |window| window.device_pixel_ratio());
+    let preview = use_preview(props.id.clone(), (width.max(height) as f64 * pixel_ratio) as u32);
+    let placeholder = use_placeholder_style(props.id.clone());
+
+    let image = match preview {
+        None => "none".to_string(),
+        Some(Some(size)) => format!(
+            "url({}/get-preview?id={}&window={}&max={})",
+            static_ctx.protocol, props.id, static_ctx.window_label, size
+        ),
+        // Without preview, the original is loaded as before.
+        Some(None) => format!(
+            "url({}/get-image?id={}&window={})",
+            static_ctx.protocol, props.id, static_ctx.window_label
+        ),
+    };
     html! {
-        <div class="thumb"
-            style={format!("background-image: url({}/get-image?id={}&window={}); aspect-ratio: {} / {};",
-            static_ctx.protocol, props.id, static_ctx.window_label, props.width, props.height)}>
+        <div class="thumb" ref={ref_pic}
+            style={format!("background-image: {}; aspect-ratio: {} / {}; {}", image, props.width, props.height, placeholder)}>
         </div>
     }
 }
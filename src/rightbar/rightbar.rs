@@ -1,7 +1,18 @@
-use yew::{function_component, html, Children, Html, Properties, Suspense};
+use futures::stream::StreamExt;
+use tauri_sys::event::listen;
+use tauri_sys::window::current_window;
+use yew::platform::spawn_local;
+use yew::{function_component, html, use_state, Callback, Children, Html, Properties, Suspense};
+use yew_hooks::use_is_first_mount;
 use yewdux::use_selector;
 
 use crate::app::Context;
+use crate::rightbar::date_shift::DateShiftTool;
+use crate::rightbar::export::ExportTool;
+use crate::rightbar::geotag::GeotagTool;
+use crate::rightbar::metadata_editor::MetadataEditor;
+use crate::rightbar::metadata_inspector::MetadataInspector;
+use crate::rightbar::picture_info::PictureInfo;
 use crate::rightbar::picture_preview::{PicturePreview, PicturesPreview};
 
 #[derive(Properties, PartialEq)]
@@ -9,10 +20,59 @@ pub struct Props {
     pub children: Children, // the field name `children` is important!
 }
 
+// Tool opened from the menu bar, editing the selected pictures
+#[derive(Clone, Copy, PartialEq)]
+enum Tool {
+    None,
+    MetadataEditor,
+    DateShift,
+    Geotag,
+    Export,
+}
+
 #[allow(non_snake_case)]
 #[function_component]
 pub fn RightBar() -> Html {
     let ids = use_selector(|ctx: &Context| ctx.get_selected_picture_ids());
+    let tool = use_state(|| Tool::None);
+
+    // Opening the tools from the menu bar
+    if use_is_first_mount() {
+        for (event, opened_tool) in [
+            ("menu-edit-metadata", Tool::MetadataEditor),
+            ("menu-shift-dates", Tool::DateShift),
+            ("menu-geotag", Tool::Geotag),
+            ("menu-export", Tool::Export),
+        ] {
+            let tool = tool.clone();
+            spawn_local(async move {
+                let mut events = listen::<()>(event).await.unwrap();
+                while let Some(e) = events.next().await {
+                    if e.window_label == Some(current_window().label()) {
+                        tool.set(opened_tool);
+                    }
+                }
+            });
+        }
+    }
+    let on_close = {
+        let tool = tool.clone();
+        Callback::from(move |_| tool.set(Tool::None))
+    };
+    let editor = match *tool {
+        _ if ids.len() == 0 => html! {},
+        Tool::None if ids.len() == 1 => html! {
+            <>
+                <PictureInfo id={ids[0].clone()}/>
+                <MetadataInspector id={ids[0].clone()}/>
+            </>
+        },
+        Tool::None => html! {},
+        Tool::MetadataEditor => html! { <MetadataEditor key={ids.join(",")} ids={(*ids).clone()} {on_close}/> },
+        Tool::DateShift => html! { <DateShiftTool key={ids.join(",")} ids={(*ids).clone()} {on_close}/> },
+        Tool::Geotag => html! { <GeotagTool key={ids.join(",")} ids={(*ids).clone()} {on_close}/> },
+        Tool::Export => html! { <ExportTool key={ids.join(",")} ids={(*ids).clone()} {on_close}/> },
+    };
 
     let fallback = html! {
         <section class="sidebar rightbar">
@@ -30,7 +90,7 @@ pub fn RightBar() -> Html {
                 <section class="sidebar rightbar">
                     <PicturePreview id={ids[0].clone()} thumbnail={false}/>
                     <div class="content">
-
+                        {editor}
                     </div>
                 </section>
             </Suspense>
@@ -48,7 +108,7 @@ pub fn RightBar() -> Html {
                 <section class="sidebar rightbar">
                     <PicturesPreview ids={preview_ids}/>
                     <div class="content">
-
+                        {editor}
                     </div>
                 </section>
             </Suspense>
@@ -1,4 +1,8 @@
 pub mod logger;
 pub mod translator;
 pub mod keystroke;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod thumbnails;
+pub mod ratings;
+pub mod tags;
+
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use futures::stream::StreamExt;
+use gloo::events::EventListener;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tauri_sys::event::listen;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+use yew::platform::spawn_local;
+use yew::{hook, use_effect_with};
+use yewdux::prelude::Dispatch;
+
+use pm_common::metadata::MetadataEditError;
+use pm_common::ratings::{ColorLabel, PictureFlag, PictureRating, RatingChange};
+
+use crate::app::Context;
+use crate::mainpane::mainpane::CacheContext;
+use crate::utils::utils::cmd_async_result;
+
+#[derive(Serialize, Deserialize)]
+struct SetPicturesRatingArgs {
+    ids: Vec<String>,
+    change: RatingChange,
+}
+
+/// Listen to the ratings changes sent by the backend. Must be called only once.
+pub fn listen_ratings_events() {
+    spawn_local(async move {
+        let mut events = listen::<HashMap<String, PictureRating>>("gallery-ratings-changed").await.unwrap();
+        while let Some(e) = events.next().await {
+            Dispatch::<CacheContext>::global().reduce_mut(|cache| cache.ratings = e.payload);
+        }
+    });
+}
+
+pub fn set_pictures_rating(ids: Vec<String>, change: RatingChange) {
+    if ids.is_empty() {
+        return;
+    }
+    spawn_local(async move {
+        if let Err(e) = cmd_async_result::<_, (), MetadataEditError>("set_pictures_rating", &SetPicturesRatingArgs { ids, change }).await {
+            warn!("Unable to write the rating to the pictures metadata: {:?}", e);
+        }
+    });
+}
+
+/// Keyboard shortcuts rating the selected pictures, from the grid, the carousel or the full view:
+/// 0 to 5 for the stars, P to pick, X to reject, U to unflag, 6 to 9 to toggle the red, yellow, green and blue labels.
+#[hook]
+pub fn use_rating_shortcuts() {
+    use_effect_with((), |_| {
+        let listener = EventListener::new(&gloo::utils::document(), "keydown", |e| {
+            let e = e.dyn_ref::<KeyboardEvent>().unwrap();
+            if e.ctrl_key() || e.meta_key() || e.alt_key() || e.repeat() || is_editing(e) {
+                return;
+            }
+            let ids = Dispatch::<Context>::global().get().get_selected_picture_ids();
+            let change = match e.key().to_lowercase().as_str() {
+                key @ ("0" | "1" | "2" | "3" | "4" | "5") => RatingChange::Stars(key.parse().unwrap()),
+                "p" => RatingChange::Flag(PictureFlag::Pick),
+                "x" => RatingChange::Flag(PictureFlag::Reject),
+                "u" => RatingChange::Flag(PictureFlag::None),
+                key @ ("6" | "7" | "8" | "9") => {
+                    let label = ColorLabel::ALL[key.parse::<usize>().unwrap() - 6];
+                    // Pressing the label of all the selected pictures removes it
+                    let cache = Dispatch::<CacheContext>::global().get();
+                    let all_labeled = ids.iter().all(|id| cache.ratings.get(id).and_then(|rating| rating.label) == Some(label));
+                    RatingChange::Label(if all_labeled { None } else { Some(label) })
+                }
+                _ => return,
+            };
+            e.prevent_default();
+            set_pictures_rating(ids, change);
+        });
+        move || drop(listener)
+    });
+}
+
+// True if the key is typed in a text field, like the metadata editor.
+fn is_editing(e: &KeyboardEvent) -> bool {
+    e.target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .map_or(false, |target| {
+            matches!(target.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") || target.is_content_editable()
+        })
+}
@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use futures::stream::StreamExt;
+use tauri_sys::event::listen;
+use yew::platform::spawn_local;
+use yewdux::prelude::Dispatch;
+
+use pm_common::hierarchy_config::HierarchyConfig;
+use pm_common::tags::TagGroup;
+
+use crate::mainpane::mainpane::CacheContext;
+
+/// Listen to the tag groups changes sent by the backend, and to the hierarchy filters updated after tags were merged or deleted.
+/// Must be called only once.
+pub fn listen_tags_events() {
+    spawn_local(async move {
+        let mut events = listen::<HashMap<String, TagGroup>>("gallery-tags-changed").await.unwrap();
+        while let Some(e) = events.next().await {
+            Dispatch::<CacheContext>::global().reduce_mut(|cache| cache.tag_groups = e.payload);
+        }
+    });
+    spawn_local(async move {
+        let mut events = listen::<HierarchyConfig>("gallery-hierarchy-changed").await.unwrap();
+        while let Some(e) = events.next().await {
+            Dispatch::<CacheContext>::global().reduce_mut(|cache| cache.hierarchy = e.payload);
+        }
+    });
+}
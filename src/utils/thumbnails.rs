@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use futures::stream::StreamExt;
+use serde::{Deserialize, Serialize};
+use tauri_sys::event::listen;
+use yew::platform::spawn_local;
+use yew::{hook, use_effect_with, use_state};
+use yewdux::prelude::{use_selector_with_deps, Dispatch};
+use yewdux::store::Store;
+
+use pm_common::thumbnails::{ThumbnailPriority, TilesInfo};
+
+use crate::mainpane::full_picture::GetImageArgs;
+use crate::mainpane::mainpane::CacheContext;
+use crate::utils::utils::{cmd_arg, cmd_async};
+
+#[derive(Clone, Debug, Default, PartialEq, Store)]
+pub struct ThumbnailsContext {
+    pub thumbnails: HashMap<String, bool>, // Whether the thumbnail could be generated, in function of the picture uid
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestThumbnailArgs {
+    id: String,
+    priority: ThumbnailPriority,
+}
+
+/// Listen to the thumbnails generated by the backend queue. Must be called only once.
+pub fn listen_thumbnails_events() {
+    spawn_local(async move {
+        let mut events = listen::<(String, bool)>("thumbnail-generated").await.unwrap();
+        while let Some(e) = events.next().await {
+            Dispatch::<ThumbnailsContext>::global().reduce_mut(|ctx| {
+                ctx.thumbnails.insert(e.payload.0, e.payload.1);
+            });
+        }
+    });
+}
+
+/// Request the thumbnail of a picture to the backend queue, cancelling the request when the component is unmounted.
+/// Priority changes only update the pending request. Returns None until the thumbnail is ready, then whether it could be generated.
+#[hook]
+pub fn use_thumbnail(id: String, priority: ThumbnailPriority) -> Option<bool> {
+    let thumbnail = use_selector_with_deps(|ctx: &ThumbnailsContext, id: &String| ctx.thumbnails.get(id).copied(), id.clone());
+
+    use_effect_with(id.clone(), move |id| {
+        let id = id.clone();
+        spawn_local({
+            let id = id.clone();
+            async move {
+                let exists = cmd_async::<RequestThumbnailArgs, bool>("request_thumbnail", &RequestThumbnailArgs { id: id.clone(), priority }).await;
+                if exists {
+                    Dispatch::<ThumbnailsContext>::global().reduce_mut(|ctx| {
+                        ctx.thumbnails.insert(id, true);
+                    });
+                }
+            }
+        });
+        move || cmd_arg("cancel_thumbnail", &GetImageArgs { id })
+    });
+    use_effect_with((id, priority), |(id, priority)| {
+        cmd_arg(
+            "update_thumbnail_priority",
+            &RequestThumbnailArgs {
+                id: id.clone(),
+                priority: *priority,
+            },
+        );
+        || {}
+    });
+
+    *thumbnail
+}
+
+/// CSS background color of the placeholder painted while the thumbnail of a picture loads.
+#[hook]
+pub fn use_placeholder_style(id: String) -> String {
+    let color = use_selector_with_deps(
+        |cache: &CacheContext, id: &String| cache.datas_cache.get(id).and_then(|picture| picture.color),
+        id,
+    );
+    color.map_or(String::new(), |(r, g, b)| format!("background-color: rgb({}, {}, {});", r, g, b))
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestPreviewArgs {
+    id: String,
+    max: u32,
+}
+
+/// Request a preview of the picture fitting in a `max` pixels square (device pixels). Returns None until the preview is ready,
+/// then the size to pass to `/get-preview`, or None if it could not be generated. The preview of the previous size is kept
+/// while a new size is generated.
+#[hook]
+pub fn use_preview(id: String, max: u32) -> Option<Option<u32>> {
+    let preview = use_state(|| None::<(String, Option<u32>)>);
+
+    use_effect_with((id.clone(), max), {
+        let preview = preview.clone();
+        move |(id, max)| {
+            // The size of the container is not known yet
+            if *max != 0 {
+                let id = id.clone();
+                let max = *max;
+                spawn_local(async move {
+                    let size = cmd_async::<RequestPreviewArgs, Option<u32>>("request_preview", &RequestPreviewArgs { id: id.clone(), max }).await;
+                    preview.set(Some((id, size)));
+                });
+            }
+            || {}
+        }
+    });
+
+    preview.as_ref().filter(|(preview_id, _)| *preview_id == id).map(|(_, size)| *size)
+}
+
+/// Request the tile pyramid of a picture once `enabled` is true. Returns None until the pyramid is built, then its
+/// description, or None if it could not be built.
+#[hook]
+pub fn use_tiles(id: String, enabled: bool) -> Option<Option<TilesInfo>> {
+    let tiles = use_state(|| None::<(String, Option<TilesInfo>)>);
+    let requested = tiles.as_ref().map_or(false, |(tiles_id, _)| *tiles_id == id);
+
+    use_effect_with((id.clone(), enabled && !requested), {
+        let tiles = tiles.clone();
+        move |(id, request)| {
+            if *request {
+                let id = id.clone();
+                spawn_local(async move {
+                    let info = cmd_async::<GetImageArgs, Option<TilesInfo>>("request_tiles", &GetImageArgs { id: id.clone() }).await;
+                    tiles.set(Some((id, info)));
+                });
+            }
+            || {}
+        }
+    });
+
+    tiles.as_ref().filter(|(tiles_id, _)| *tiles_id == id).map(|(_, info)| info.clone())
+}
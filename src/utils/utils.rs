@@ -11,6 +11,9 @@ extern "C" {
 
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"], js_name = "invoke")]
     fn invoke_tauri(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"], js_name = "invoke", catch)]
+    async fn invoke_tauri_result(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
 }
 
 /// Invoke a tauri command without arguments and without return value.
@@ -33,6 +36,14 @@ pub async fn cmd_async_get<R: DeserializeOwned>(cmd: &str) -> R {
 pub async fn cmd_async<A: Serialize, R: DeserializeOwned>(cmd: &str, arg: &A) -> R {
     invoke(cmd, arg).await.expect(format!("Unable to invoke async command {}", cmd).as_str())
 }
+/// Invoke a tauri command returning a Result, the error being returned to the caller instead of panicking. Async function
+pub async fn cmd_async_result<A: Serialize, R: DeserializeOwned, E: DeserializeOwned>(cmd: &str, arg: &A) -> Result<R, E> {
+    let arg = serde_wasm_bindgen::to_value(arg).expect(format!("Unable to serialize argument for command {}", cmd).as_str());
+    match invoke_tauri_result(cmd, arg).await {
+        Ok(value) => Ok(serde_wasm_bindgen::from_value(value).expect(format!("Unable to deserialize result of command {}", cmd).as_str())),
+        Err(error) => Err(serde_wasm_bindgen::from_value(error).expect(format!("Unable to deserialize error of command {}", cmd).as_str())),
+    }
+}
 
 pub fn get_non_null_ref(ref_1: NodeRef, ref_2: NodeRef) -> Option<HtmlElement> {
     if let Some(element) = ref_1.cast::<HtmlElement>() {
@@ -1 +1,192 @@
-hello = Have a good day !
\ No newline at end of file
+hello = Have a good day !
+
+## Thumbnails batch generation
+thumbnails-batch-progress = Generating thumbnails: { $done } / { $total }
+thumbnails-batch-eta = { $minutes } min { $seconds } s remaining
+thumbnails-batch-cancel = Cancel
+thumbnails-cleaned = { $count ->
+    [one] 1 orphaned thumbnail deleted, { $size } MB reclaimed
+   *[other] { $count } orphaned thumbnails deleted, { $size } MB reclaimed
+}
+thumbnails-cleaned-close = Close
+
+## Metadata editor
+metadata-editor-title = Edit metadata
+metadata-title = Title
+metadata-description = Description
+metadata-keywords = Keywords (comma separated)
+metadata-rating = Rating (-1 to reject, 0 to 5)
+metadata-date = Date taken
+metadata-latitude = Latitude
+metadata-longitude = Longitude
+metadata-altitude = Altitude (m)
+metadata-camera-make = Camera make
+metadata-camera-model = Camera model
+metadata-copyright = Copyright
+metadata-multiple-values = Multiple values
+metadata-cancel = Cancel
+metadata-save = Save
+metadata-error-invalid-number = Coordinates must be numbers.
+metadata-error-invalid-date = The date is invalid.
+metadata-error-invalid-latitude = The latitude must be between -90 and 90.
+metadata-error-invalid-longitude = The longitude must be between -180 and 180.
+metadata-error-invalid-rating = The rating must be between -1 and 5.
+metadata-error-write-failed = { $count ->
+    [one] The metadata of one picture could not be written.
+   *[other] The metadata of { $count } pictures could not be written.
+}
+
+## Date shift
+date-shift-title = Shift dates
+date-shift-mode-offset = Offset
+date-shift-mode-reference = Reference picture
+date-shift-days = Days
+date-shift-hours = Hours
+date-shift-minutes = Minutes
+date-shift-seconds = Seconds
+date-shift-reference = Reference picture
+date-shift-reference-date = Real date of the reference picture
+date-shift-reference-hint = The selected pictures taken with the same camera are shifted by the same offset.
+date-shift-timezone = Timezone (optional)
+date-shift-preview = Preview
+date-shift-preview-count = { $count ->
+    [one] One picture will be shifted:
+   *[other] { $count } pictures will be shifted:
+}
+date-shift-apply = Apply
+metadata-error-invalid-timezone = The timezone must be formatted as +HH:MM or -HH:MM.
+metadata-error-reference-without-date = The reference picture has no date.
+metadata-error-track-read-failed = A track file could not be read.
+metadata-error-no-track-points = The tracks have no timestamped point.
+
+## Picture info
+picture-info-title = Title
+picture-info-description = Description
+picture-info-keywords = Keywords
+picture-info-rating = Rating
+picture-info-rating-rejected = Rejected
+picture-info-file = File
+picture-info-format = Format
+picture-info-file-size = Size
+picture-info-dimensions = Dimensions
+picture-info-date = Date taken
+picture-info-date-source = Date source
+picture-info-date-source-original = EXIF capture date
+picture-info-date-source-create-date = EXIF digitization date
+picture-info-date-source-xmp = XMP creation date
+picture-info-date-source-file-name = File name
+picture-info-date-source-file-modified = File modification date
+picture-info-camera = Camera
+picture-info-lens = Lens
+picture-info-focal-length = Focal length
+picture-info-exposure-time = Exposure
+picture-info-f-number = Aperture
+picture-info-iso = ISO
+picture-info-exposure-bias = Exposure bias
+picture-info-flash = Flash
+picture-info-flash-fired = Fired
+picture-info-flash-not-fired = Did not fire
+picture-info-metering = Metering
+picture-info-metering-average = Average
+picture-info-metering-center-weighted = Center weighted
+picture-info-metering-spot = Spot
+picture-info-metering-multi-spot = Multi spot
+picture-info-metering-pattern = Pattern
+picture-info-metering-partial = Partial
+picture-info-white-balance = White balance
+picture-info-white-balance-auto = Auto
+picture-info-white-balance-manual = Manual
+picture-info-software = Software
+picture-info-place = Place
+picture-info-location = Location
+picture-info-gps-direction = Direction
+picture-info-unknown = Unknown
+
+## Fast filters
+
+fast-filter-stars = Minimum rating
+fast-filter-any = Any
+fast-filter-flag = Flag
+fast-filter-pick = Picked
+fast-filter-unflagged = Unflagged
+fast-filter-rejected = Rejected
+fast-filter-label = Color label
+fast-filter-clear = Clear filters
+
+## Metadata inspector
+
+metadata-inspector-title = All metadata
+metadata-inspector-search = Search tags or values
+metadata-inspector-no-metadata = The metadata of this picture can't be read.
+metadata-inspector-no-tags = No matching tag.
+metadata-inspector-copy = Copy to clipboard
+
+## Geotagging
+
+geotag-title = Geotag from tracks
+geotag-add-tracks = Add GPX, KML or GeoJSON tracks
+geotag-clock-offset-hint = Camera clock offset, added to the capture time of the pictures.
+geotag-clock-offset-hours = Hours
+geotag-clock-offset-minutes = Minutes
+geotag-clock-offset-seconds = Seconds
+geotag-timezone = Timezone of the pictures without timezone (±HH:MM)
+geotag-max-gap = Maximum gap with the track (minutes)
+geotag-overwrite = Overwrite existing locations
+geotag-preview = Preview
+geotag-apply = Write locations
+geotag-no-match = No match
+geotag-preview-count = { $located } of { $count ->
+    [one] { $count } picture located
+   *[other] { $count } pictures located
+}
+
+## Export
+
+export-title = { $count ->
+    [one] Export one picture
+   *[other] Export { $count } pictures
+}
+export-no-destination = No destination directory
+export-choose-destination = Choose…
+export-strip = Remove from the copies
+export-strip-all = All metadata
+export-strip-gps = GPS location
+export-strip-serials = Camera and lens serial numbers
+export-strip-uid = Pictures Manager identifier
+export-home-zones = Home zones
+export-home-zones-hint = The location of pictures taken in a home zone is always removed. { $count ->
+    [0] No selected picture is in a home zone.
+    [one] One selected picture is in a home zone.
+   *[other] { $count } selected pictures are in a home zone.
+}
+export-home-zone-name = Name
+export-home-zone-radius = Radius (meters)
+export-add-home-zone = Add
+export-apply = Export
+export-close = Close
+export-done = { $count ->
+    [one] One picture exported.
+   *[other] { $count } pictures exported.
+}
+export-error-invalid-destination = The destination directory does not exist.
+export-error-failed = { $count ->
+    [one] One picture could not be exported.
+   *[other] { $count } pictures could not be exported.
+}
+
+## Tags
+
+tag-error-group-not-found = This tag group does not exist anymore.
+tag-error-tag-not-found = This tag does not exist anymore.
+tag-error-empty-name = The name can't be empty.
+tag-error-separator-in-group-name = Group names can't contain |, used to separate the keywords levels.
+tag-error-name-already-used = This name is already used.
+tag-error-invalid-color = The color must be formatted as #RRGGBB.
+tag-error-exclusive-conflict = { $count ->
+    [one] One picture would have several tags of this exclusive group.
+   *[other] { $count } pictures would have several tags of this exclusive group.
+}
+tag-error-write-failed = { $count ->
+    [one] The keywords of one picture could not be written.
+   *[other] The keywords of { $count } pictures could not be written.
+}
@@ -5,6 +5,8 @@ menu-bar-file = _File
 menu-bar-file-open-gallery = _Open gallery
 menu-bar-file-recent-galleries = Open _recent gallery
 menu-bar-file-new-gallery = _New gallery
+# Export copies of the selected pictures
+menu-bar-file-export = _Export pictures
 # Close the current window/gallery
 menu-bar-file-close-gallery = _Close gallery
 # Quit the app
@@ -23,6 +25,12 @@ menu-bar-edit-select-all = _Select all
 menu-bar-tools = _Tools
 menu-bar-tools-update-gallery = _Update gallery
 menu-bar-tools-edit-exif = _Edit EXIF data
+menu-bar-tools-shift-dates = _Shift dates
+menu-bar-tools-geotag = Geotag from _tracks
+menu-bar-tools-gen-thumbnails = _Generate all thumbnails
+# Regenerate the thumbnails made with other rendering settings (resampling filter, sharpening)
+menu-bar-tools-regen-thumbnails = _Regenerate thumbnails
+menu-bar-tools-clean-thumbnails = _Clean thumbnails
 
 menu-bar-view = View
 menu-bar-window = Window
@@ -1 +1,192 @@
-hello = Bien le bonjour !
\ No newline at end of file
+hello = Bien le bonjour !
+
+## Thumbnails batch generation
+thumbnails-batch-progress = Génération des miniatures : { $done } / { $total }
+thumbnails-batch-eta = { $minutes } min { $seconds } s restantes
+thumbnails-batch-cancel = Annuler
+thumbnails-cleaned = { $count ->
+    [one] 1 miniature orpheline supprimée, { $size } Mo libérés
+   *[other] { $count } miniatures orphelines supprimées, { $size } Mo libérés
+}
+thumbnails-cleaned-close = Fermer
+
+## Metadata editor
+metadata-editor-title = Modifier les métadonnées
+metadata-title = Titre
+metadata-description = Description
+metadata-keywords = Mots-clés (séparés par des virgules)
+metadata-rating = Note (-1 pour rejeter, 0 à 5)
+metadata-date = Date de prise de vue
+metadata-latitude = Latitude
+metadata-longitude = Longitude
+metadata-altitude = Altitude (m)
+metadata-camera-make = Marque de l'appareil
+metadata-camera-model = Modèle de l'appareil
+metadata-copyright = Copyright
+metadata-multiple-values = Valeurs multiples
+metadata-cancel = Annuler
+metadata-save = Enregistrer
+metadata-error-invalid-number = Les coordonnées doivent être des nombres.
+metadata-error-invalid-date = La date est invalide.
+metadata-error-invalid-latitude = La latitude doit être comprise entre -90 et 90.
+metadata-error-invalid-longitude = La longitude doit être comprise entre -180 et 180.
+metadata-error-invalid-rating = La note doit être comprise entre -1 et 5.
+metadata-error-write-failed = { $count ->
+    [one] Les métadonnées d'une image n'ont pas pu être écrites.
+   *[other] Les métadonnées de { $count } images n'ont pas pu être écrites.
+}
+
+## Date shift
+date-shift-title = Décaler les dates
+date-shift-mode-offset = Décalage
+date-shift-mode-reference = Image de référence
+date-shift-days = Jours
+date-shift-hours = Heures
+date-shift-minutes = Minutes
+date-shift-seconds = Secondes
+date-shift-reference = Image de référence
+date-shift-reference-date = Date réelle de l'image de référence
+date-shift-reference-hint = Les images sélectionnées prises avec le même appareil sont décalées du même écart.
+date-shift-timezone = Fuseau horaire (facultatif)
+date-shift-preview = Aperçu
+date-shift-preview-count = { $count ->
+    [one] Une image sera décalée :
+   *[other] { $count } images seront décalées :
+}
+date-shift-apply = Appliquer
+metadata-error-invalid-timezone = Le fuseau horaire doit être au format +HH:MM ou -HH:MM.
+metadata-error-reference-without-date = L'image de référence n'a pas de date.
+metadata-error-track-read-failed = Un fichier de trace n'a pas pu être lu.
+metadata-error-no-track-points = Les traces n'ont aucun point horodaté.
+
+## Picture info
+picture-info-title = Titre
+picture-info-description = Description
+picture-info-keywords = Mots-clés
+picture-info-rating = Note
+picture-info-rating-rejected = Rejetée
+picture-info-file = Fichier
+picture-info-format = Format
+picture-info-file-size = Taille
+picture-info-dimensions = Dimensions
+picture-info-date = Date de prise de vue
+picture-info-date-source = Source de la date
+picture-info-date-source-original = Date de prise de vue EXIF
+picture-info-date-source-create-date = Date de numérisation EXIF
+picture-info-date-source-xmp = Date de création XMP
+picture-info-date-source-file-name = Nom du fichier
+picture-info-date-source-file-modified = Date de modification du fichier
+picture-info-camera = Appareil
+picture-info-lens = Objectif
+picture-info-focal-length = Focale
+picture-info-exposure-time = Exposition
+picture-info-f-number = Ouverture
+picture-info-iso = ISO
+picture-info-exposure-bias = Correction d'exposition
+picture-info-flash = Flash
+picture-info-flash-fired = Déclenché
+picture-info-flash-not-fired = Non déclenché
+picture-info-metering = Mesure
+picture-info-metering-average = Moyenne
+picture-info-metering-center-weighted = Pondérée centrale
+picture-info-metering-spot = Spot
+picture-info-metering-multi-spot = Multi-spot
+picture-info-metering-pattern = Matricielle
+picture-info-metering-partial = Partielle
+picture-info-white-balance = Balance des blancs
+picture-info-white-balance-auto = Automatique
+picture-info-white-balance-manual = Manuelle
+picture-info-software = Logiciel
+picture-info-place = Lieu
+picture-info-location = Position
+picture-info-gps-direction = Direction
+picture-info-unknown = Inconnu
+
+## Fast filters
+
+fast-filter-stars = Note minimale
+fast-filter-any = Toutes
+fast-filter-flag = Marqueur
+fast-filter-pick = Retenues
+fast-filter-unflagged = Non marquées
+fast-filter-rejected = Rejetées
+fast-filter-label = Étiquette de couleur
+fast-filter-clear = Effacer les filtres
+
+## Metadata inspector
+
+metadata-inspector-title = Toutes les métadonnées
+metadata-inspector-search = Rechercher des balises ou des valeurs
+metadata-inspector-no-metadata = Les métadonnées de cette image ne peuvent pas être lues.
+metadata-inspector-no-tags = Aucune balise correspondante.
+metadata-inspector-copy = Copier dans le presse-papiers
+
+## Geotagging
+
+geotag-title = Géolocaliser depuis des traces
+geotag-add-tracks = Ajouter des traces GPX, KML ou GeoJSON
+geotag-clock-offset-hint = Décalage de l'horloge de l'appareil, ajouté à l'heure de prise de vue des images.
+geotag-clock-offset-hours = Heures
+geotag-clock-offset-minutes = Minutes
+geotag-clock-offset-seconds = Secondes
+geotag-timezone = Fuseau horaire des images sans fuseau (±HH:MM)
+geotag-max-gap = Écart maximal avec la trace (minutes)
+geotag-overwrite = Remplacer les positions existantes
+geotag-preview = Aperçu
+geotag-apply = Écrire les positions
+geotag-no-match = Aucune correspondance
+geotag-preview-count = { $located } sur { $count ->
+    [one] { $count } image localisée
+   *[other] { $count } images localisées
+}
+
+## Export
+
+export-title = { $count ->
+    [one] Exporter une image
+   *[other] Exporter { $count } images
+}
+export-no-destination = Aucun dossier de destination
+export-choose-destination = Choisir…
+export-strip = Supprimer des copies
+export-strip-all = Toutes les métadonnées
+export-strip-gps = La position GPS
+export-strip-serials = Les numéros de série du boîtier et de l'objectif
+export-strip-uid = L'identifiant de Pictures Manager
+export-home-zones = Zones privées
+export-home-zones-hint = La position des images prises dans une zone privée est toujours supprimée. { $count ->
+    [0] Aucune image sélectionnée n'est dans une zone privée.
+    [one] Une image sélectionnée est dans une zone privée.
+   *[other] { $count } images sélectionnées sont dans une zone privée.
+}
+export-home-zone-name = Nom
+export-home-zone-radius = Rayon (mètres)
+export-add-home-zone = Ajouter
+export-apply = Exporter
+export-close = Fermer
+export-done = { $count ->
+    [one] Une image exportée.
+   *[other] { $count } images exportées.
+}
+export-error-invalid-destination = Le dossier de destination n'existe pas.
+export-error-failed = { $count ->
+    [one] Une image n'a pas pu être exportée.
+   *[other] { $count } images n'ont pas pu être exportées.
+}
+
+## Tags
+
+tag-error-group-not-found = Ce groupe de tags n'existe plus.
+tag-error-tag-not-found = Ce tag n'existe plus.
+tag-error-empty-name = Le nom ne peut pas être vide.
+tag-error-separator-in-group-name = Les noms de groupes ne peuvent pas contenir |, qui sépare les niveaux des mots-clés.
+tag-error-name-already-used = Ce nom est déjà utilisé.
+tag-error-invalid-color = La couleur doit être au format #RRGGBB.
+tag-error-exclusive-conflict = { $count ->
+    [one] Une image aurait plusieurs tags de ce groupe exclusif.
+   *[other] { $count } images auraient plusieurs tags de ce groupe exclusif.
+}
+tag-error-write-failed = { $count ->
+    [one] Les mots-clés d'une image n'ont pas pu être écrits.
+   *[other] Les mots-clés de { $count } images n'ont pas pu être écrits.
+}
@@ -5,6 +5,8 @@ menu-bar-file = _Fichier
 menu-bar-file-open-gallery = _Ouvrir une gallerie
 menu-bar-file-recent-galleries = Ouvrir une gallerie _récente
 menu-bar-file-new-gallery = _Nouvelle gallerie
+# Export copies of the selected pictures
+menu-bar-file-export = _Exporter les images
 # Close the current window/gallery
 menu-bar-file-close-gallery = _Fermer la gallerie
 # Quit the app
@@ -23,6 +25,11 @@ menu-bar-edit-select-all = Tout _sélectionner
 menu-bar-tools = _Outils
 menu-bar-tools-update-gallery = _Actualiser la galerie
 menu-bar-tools-edit-exif = _Corriger les données EXIF
+menu-bar-tools-shift-dates = _Décaler les dates
+menu-bar-tools-geotag = Géolocaliser depuis des _traces
+menu-bar-tools-gen-thumbnails = _Générer toutes les miniatures
+menu-bar-tools-regen-thumbnails = _Régénérer les miniatures
+menu-bar-tools-clean-thumbnails = _Nettoyer les miniatures
 
 menu-bar-view = Présentation
 menu-bar-window = Fenêtre